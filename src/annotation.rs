@@ -0,0 +1,378 @@
+use crate::{Canvas, CanvasError, DataSet, TimeUnit, format_time, get_cell, parse_time};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Value along the x axis, given as a number or as a date
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Abscissa {
+    /// Number in the unit of the x values, such as milliseconds since the epoch
+    Number(f64),
+
+    /// Date, as seconds since 1970-01-01T00:00:00Z
+    Date(f64),
+}
+
+impl Abscissa {
+    /// x coordinate, numbers being timestamps in unit if any
+    pub fn get(&self, unit: Option<TimeUnit>) -> f64 {
+        match (self, unit) {
+            (Abscissa::Number(x), Some(unit)) => x / unit.per_second(),
+            (Abscissa::Number(x), None) | (Abscissa::Date(x), _) => *x,
+        }
+    }
+}
+
+/// Numbers and dates are not comparable without the unit of the numbers
+impl PartialOrd for Abscissa {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Abscissa::Number(a), Abscissa::Number(b)) | (Abscissa::Date(a), Abscissa::Date(b)) => {
+                a.partial_cmp(b)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for Abscissa {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Abscissa::Number(x) => write!(f, "{x}"),
+            Abscissa::Date(t) => write!(f, "{}", format_time(*t, "%Y-%m-%dT%H:%M:%S")),
+        }
+    }
+}
+
+impl FromStr for Abscissa {
+    type Err = String;
+
+    /// A number, or a date such as `2024-03-01T12:00` (see [parse_time])
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().parse() {
+            Ok(x) => Ok(Abscissa::Number(x)),
+            Err(_) => parse_time(s)
+                .map(Abscissa::Date)
+                .ok_or_else(|| format!("invalid x value '{s}': expected a number or a date")),
+        }
+    }
+}
+
+/// Interval between two values of an axis, x intervals accepting dates
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Span<T = f64> {
+    pub low: T,
+    pub high: T,
+}
+
+impl<T: Display> Display for Span<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.low, self.high)
+    }
+}
+
+impl<T: FromStr + PartialOrd> FromStr for Span<T> {
+    type Err = String;
+
+    /// low..high, such as `200..300` or `2024-03-01..2024-03-02`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid span '{s}': expected low..high");
+        let (low, high) = s.split_once("..").ok_or_else(invalid)?;
+        let low: T = low.trim().parse().map_err(|_| invalid())?;
+        let high: T = high.trim().parse().map_err(|_| invalid())?;
+        if low.partial_cmp(&high) != Some(Ordering::Less) {
+            return Err(invalid());
+        }
+        Ok(Self { low, high })
+    }
+}
+
+/// Text written at data coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note {
+    pub x: Abscissa,
+    pub y: f64,
+    pub text: String,
+}
+
+impl Display for Note {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.text)
+    }
+}
+
+impl FromStr for Note {
+    type Err = String;
+
+    /// x,y,text, the text starting at the point, such as `1700000000,250,deploy`
+    /// or `2024-03-01T12:00,250,deploy`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid annotation '{s}': expected x,y,text");
+        let mut items = s.splitn(3, ',');
+        let (Some(x), Some(y), Some(text)) = (items.next(), items.next(), items.next()) else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            x: x.trim().parse().map_err(|_| invalid())?,
+            y: y.trim().parse().map_err(|_| invalid())?,
+            text: text.to_string(),
+        })
+    }
+}
+
+/// Mark drawn over a plot, at data coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Annotation {
+    /// Line across the canvas at a y value
+    HLine(f64),
+
+    /// Line across the canvas at an x value
+    VLine(Abscissa),
+
+    /// Text starting at a point
+    Text(Note),
+
+    /// Band shaded between two x values
+    XRegion(Span<Abscissa>),
+
+    /// Band shaded between two y values
+    YRegion(Span),
+}
+
+impl Annotation {
+    /// Order of drawing, text being kept above lines, themselves above regions
+    fn depth(&self) -> usize {
+        match self {
+            Annotation::XRegion(_) | Annotation::YRegion(_) => 0,
+            Annotation::HLine(_) | Annotation::VLine(_) => 1,
+            Annotation::Text(_) => 2,
+        }
+    }
+}
+
+impl Canvas {
+    /// Draw an annotation with the current ranges, skipping what is out of them,
+    /// x numbers being timestamps in unit if any
+    ///
+    /// Text overwrites the plot, lines only hide axes and regions,
+    /// regions only shade empty cells.
+    pub(crate) fn draw_annotation(
+        &mut self,
+        annotation: &Annotation,
+        unit: Option<TimeUnit>,
+    ) -> Result<(), CanvasError> {
+        let ((x_min, x_max), (y_min, y_max)) = (self.x_range, self.y_range);
+        if !(x_min < x_max && y_min < y_max) {
+            return Ok(());
+        }
+        let line = |canvas: &Canvas, y| canvas.get_line(y).ok().filter(|&l| l < canvas.height);
+        let column = |x| get_cell(x, x_min, x_max, self.width).ok();
+        match annotation {
+            Annotation::HLine(y) => {
+                if let Some(line) = line(self, *y) {
+                    for column in 0..self.width {
+                        self.set_reference_cell(line, column, '╌')?;
+                    }
+                }
+            }
+            Annotation::VLine(x) => {
+                if let Some(column) = column(x.get(unit)) {
+                    for line in 0..self.height {
+                        self.set_reference_cell(line, column, '╎')?;
+                    }
+                }
+            }
+            Annotation::Text(note) => {
+                if let (Some(line), Some(column)) = (line(self, note.y), column(note.x.get(unit))) {
+                    let text: String = note.text.chars().take(self.width - column).collect();
+                    self.draw_text(line, column, &text)?;
+                }
+            }
+            Annotation::XRegion(span) => {
+                let (low, high) = (span.low.get(unit), span.high.get(unit));
+                if high >= x_min && low <= x_max {
+                    let (c0, c1) = (self.get_clamped_column(low), self.get_clamped_column(high));
+                    for line in 0..self.height {
+                        for column in c0..=c1 {
+                            self.set_region_cell(line, column)?;
+                        }
+                    }
+                }
+            }
+            Annotation::YRegion(span) => {
+                if span.high >= y_min && span.low <= y_max {
+                    let (l0, l1) = (
+                        self.get_clamped_line(span.high),
+                        self.get_clamped_line(span.low),
+                    );
+                    for line in l0..=l1 {
+                        for column in 0..self.width {
+                            self.set_region_cell(line, column)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Shade a cell of a region, if it is empty or part of the grid
+    fn set_region_cell(&mut self, line: usize, column: usize) -> Result<(), CanvasError> {
+        match self.get_mut_cell(line, column) {
+            Some(' ' | '·') => self.set_cell(line, column, '░'),
+            _ => Ok(()),
+        }
+    }
+
+    /// Set a cell of a reference line, unless it holds data
+    fn set_reference_cell(
+        &mut self,
+        line: usize,
+        column: usize,
+        value: char,
+    ) -> Result<(), CanvasError> {
+        match self.get_mut_cell(line, column) {
+            Some(' ' | '─' | '│' | '┼' | '┌' | '┐' | '└' | '┘' | '░' | '╌' | '╎' | '·') | None => {
+                self.set_cell(line, column, value)
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+impl DataSet {
+    /// Add a mark drawn over plots of any kind
+    pub fn add_annotation(&mut self, annotation: Annotation) {
+        self.annotations.push(annotation);
+    }
+
+    /// Draw the annotations, regions first and text last
+    pub(crate) fn draw_annotations(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
+        let mut annotations: Vec<&Annotation> = self.annotations.iter().collect();
+        annotations.sort_by_key(|a| a.depth());
+        for annotation in annotations {
+            canvas.draw_annotation(annotation, self.time_unit)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlotKind;
+
+    #[test]
+    fn annotation_parse() {
+        let span: Span = "200..300".parse().unwrap();
+        assert_eq!((span.low, span.high), (200.0, 300.0));
+        assert!("300..200".parse::<Span>().is_err());
+        assert!("200".parse::<Span>().is_err());
+        let note: Note = "1700000000,250,deploy, v2".parse().unwrap();
+        assert_eq!((note.x, note.y), (Abscissa::Number(1700000000.0), 250.0));
+        assert_eq!(note.text, "deploy, v2");
+        assert_eq!(note.to_string(), "1700000000,250,deploy, v2");
+        assert!("1,deploy".parse::<Note>().is_err());
+
+        let date: Abscissa = "2024-03-01T12:00".parse().unwrap();
+        assert_eq!(date, Abscissa::Date(1709294400.0));
+        assert_eq!(date.to_string(), "2024-03-01T12:00:00");
+        assert_eq!(date.get(Some(TimeUnit::Milliseconds)), 1709294400.0);
+        assert_eq!(Abscissa::Number(5e3).get(Some(TimeUnit::Milliseconds)), 5.0);
+        let span: Span<Abscissa> = "2024-03-01..2024-03-02".parse().unwrap();
+        assert_eq!(span.high, Abscissa::Date(1709337600.0));
+        // a date and a number cannot be ordered
+        assert!("2024-03-01..2".parse::<Span<Abscissa>>().is_err());
+        assert!("2024-03-01".parse::<Span>().is_err());
+    }
+
+    #[test]
+    fn annotations_timestamps() {
+        let text = "t,a\n1709294400000,1\n1709380800000,2\n";
+        let mut dataset = DataSet::from_csv(text).unwrap();
+        dataset.set_time_axis(TimeUnit::Milliseconds);
+        dataset.add_annotation(Annotation::VLine("1709337600000".parse().unwrap()));
+        dataset.add_annotation(Annotation::Text(
+            "2024-03-02T00:00,1.5,SLO".parse().unwrap(),
+        ));
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        let text = canvas.to_string();
+        let vline: Vec<usize> = text.lines().filter_map(|l| l.find('╎')).collect();
+        assert!(vline.len() > 20);
+        // the text starts on the line
+        let slo = text.lines().find(|l| l.contains("SLO")).unwrap();
+        assert_eq!(slo.find("SLO"), vline.first().copied());
+    }
+
+    #[test]
+    fn annotations_drawn() {
+        let mut dataset = DataSet::from_csv("x,a\n0,0\n10,10\n").unwrap();
+        dataset.add_annotation(Annotation::HLine(5.0));
+        dataset.add_annotation(Annotation::HLine(10.0));
+        dataset.add_annotation(Annotation::VLine(Abscissa::Number(20.0)));
+        dataset.add_annotation(Annotation::XRegion(Span {
+            low: Abscissa::Number(2.0),
+            high: Abscissa::Number(4.0),
+        }));
+        dataset.add_annotation(Annotation::Text(Note {
+            x: Abscissa::Number(6.0),
+            y: 5.0,
+            text: "SLO".to_string(),
+        }));
+        for kind in [PlotKind::Point, PlotKind::Density] {
+            let mut canvas = Canvas::new();
+            dataset.draw_into(&mut canvas, kind).unwrap();
+            assert!(canvas.to_string().contains('░'));
+        }
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        let text = canvas.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        // out of the x range
+        assert!(!text.contains('╎'));
+        let slo = lines.iter().find(|l| l.contains("SLO")).unwrap();
+        assert!(slo.starts_with("╌╌"));
+        assert!(slo.contains("╌SLO╌"));
+        assert!(lines.iter().filter(|l| l.contains('░')).count() > 20);
+        // neither regions nor lines hide the points, found at the same cells
+        // as without annotations
+        let plain = DataSet::from_csv("x,a\n0,0\n10,10\n").unwrap();
+        let mut plain_canvas = Canvas::new();
+        plain.draw_into(&mut plain_canvas, PlotKind::Point).unwrap();
+        let markers: Vec<(usize, usize)> = (0..plain_canvas.height)
+            .flat_map(|line| (0..plain_canvas.width).map(move |column| (line, column)))
+            .filter(|&(line, column)| plain_canvas.cells[line][column] == 'a')
+            .collect();
+        assert_eq!(markers.len(), 2);
+        for (line, column) in markers {
+            assert_eq!(canvas.cells[line][column], 'a');
+        }
+        assert_eq!(text.matches('a').count(), 2);
+    }
+
+    #[test]
+    fn annotations_grid() {
+        let mut dataset = DataSet::from_csv("x,a\n0,0\n10,10\n").unwrap();
+        dataset.set_grid(true);
+        dataset.add_annotation(Annotation::XRegion(Span {
+            low: Abscissa::Number(2.0),
+            high: Abscissa::Number(4.0),
+        }));
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        let (c0, c1) = (
+            canvas.get_clamped_column(2.0),
+            canvas.get_clamped_column(4.0),
+        );
+        // the region is shaded over the grid, which remains outside of it
+        for cells in &canvas.cells {
+            assert!(cells[c0..=c1].iter().all(|&c| c != '·'));
+        }
+        let mut grid = canvas.cells.iter().filter(|cells| cells.contains(&'·'));
+        assert!(grid.clone().count() > 3);
+        assert!(grid.all(|cells| cells[c0] == '░'));
+        assert!(canvas.to_string().contains('·'));
+    }
+}
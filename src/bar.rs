@@ -0,0 +1,317 @@
+use crate::{Canvas, CanvasError, Color, get_cell};
+use clap::ValueEnum;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// How bars of several datasets are laid out in a category
+#[derive(Debug, Default, Copy, Clone, PartialEq, ValueEnum)]
+pub enum BarMode {
+    /// Side by side
+    #[default]
+    Grouped,
+
+    /// On top of each other
+    Stacked,
+}
+
+impl Display for BarMode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            BarMode::Grouped => write!(f, "grouped"),
+            BarMode::Stacked => write!(f, "stacked"),
+        }
+    }
+}
+
+/// A bar, positioned within its category
+struct Bar {
+    symbole: char,
+    color: Option<Color>,
+    /// Cells between the start of the category and the bar
+    offset: usize,
+    thickness: usize,
+    start: f64,
+    end: f64,
+}
+
+/// Value of each dataset in each category
+#[derive(Debug, Default)]
+pub struct Bars {
+    categories: Vec<String>,
+    /// (symbole, color, value per category)
+    series: Vec<(char, Option<Color>, Vec<f64>)>,
+}
+
+impl Bars {
+    pub fn new(categories: Vec<String>) -> Self {
+        Self {
+            categories,
+            series: Vec::new(),
+        }
+    }
+
+    /// Add a dataset, with one value per category
+    pub fn add_series(&mut self, symbole: char, values: Vec<f64>) {
+        self.add_colored_series(symbole, None, values);
+    }
+
+    /// Add a dataset whose bars have a color, with one value per category
+    pub fn add_colored_series(&mut self, symbole: char, color: Option<Color>, values: Vec<f64>) {
+        assert_eq!(values.len(), self.categories.len());
+        self.series.push((symbole, color, values));
+    }
+
+    /// Range of the values, including 0
+    fn range(&self, mode: BarMode) -> (f64, f64) {
+        let values: Vec<f64> = match mode {
+            BarMode::Grouped => self
+                .series
+                .iter()
+                .flat_map(|(_, _, v)| v.iter().copied())
+                .collect(),
+            BarMode::Stacked => (0..self.categories.len())
+                .flat_map(|c| {
+                    let values = self.series.iter().map(move |(_, _, v)| v[c]);
+                    [
+                        values.clone().filter(|&x| x > 0.0).sum::<f64>(),
+                        values.filter(|&x| x < 0.0).sum::<f64>(),
+                    ]
+                })
+                .collect(),
+        };
+        let (min, max) = values
+            .into_iter()
+            .filter(|x| !x.is_nan())
+            .fold((0.0, 0.0), |(mi, ma): (f64, f64), x| (mi.min(x), ma.max(x)));
+        if min < max { (min, max) } else { (0.0, 1.0) }
+    }
+
+    /// Bars of each category
+    fn bars(&self, slot: usize, mode: BarMode) -> Vec<Vec<Bar>> {
+        let thickness = match mode {
+            BarMode::Grouped => (slot.saturating_sub(1) / self.series.len().max(1)).max(1),
+            BarMode::Stacked => slot.saturating_sub(1).max(1),
+        };
+        (0..self.categories.len())
+            .map(|c| {
+                let (mut positive, mut negative) = (0.0, 0.0);
+                self.series
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, _, v))| !v[c].is_nan() && v[c] != 0.0)
+                    .map(|(idx, &(symbole, color, ref v))| match mode {
+                        BarMode::Grouped => Bar {
+                            symbole,
+                            color,
+                            offset: idx * thickness,
+                            thickness,
+                            start: 0.0,
+                            end: v[c],
+                        },
+                        BarMode::Stacked => {
+                            let base = if v[c] > 0.0 {
+                                &mut positive
+                            } else {
+                                &mut negative
+                            };
+                            let start = *base;
+                            *base += v[c];
+                            Bar {
+                                symbole,
+                                color,
+                                offset: 0,
+                                thickness,
+                                start,
+                                end: *base,
+                            }
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Draw bars growing upward, category labels being on the last line
+    pub fn draw_into(&self, canvas: &mut Canvas, mode: BarMode) -> Result<(), CanvasError> {
+        if self.categories.is_empty() || canvas.height < 2 {
+            return Err(CanvasError::NoData);
+        }
+        let (min, max) = self.range(mode);
+        let lines = canvas.height - 1;
+        let line = |v: f64| lines - 1 - get_cell(v, min, max, lines).expect("value is in range");
+        let slot = (canvas.width / self.categories.len()).max(1);
+
+        for (idx, bars) in self.bars(slot, mode).into_iter().enumerate() {
+            for Bar {
+                symbole,
+                color,
+                offset,
+                thickness,
+                start,
+                end,
+            } in bars
+            {
+                // the line of the start value belongs to the previous bar
+                let (s, e) = (line(start), line(end));
+                let lines = if end > start {
+                    e..=s.saturating_sub(1).max(e)
+                } else {
+                    (s + 1).min(e)..=e
+                };
+                let first = idx * slot + offset;
+                canvas.set_pen(color);
+                for c in first..(first + thickness).min(canvas.width) {
+                    for l in lines.clone() {
+                        canvas.set_cell(l, c, symbole)?;
+                    }
+                }
+            }
+        }
+        canvas.set_pen(None);
+        let zero = line(0.0);
+        for c in 0..canvas.width {
+            canvas.set_cell(zero, c, '─')?;
+        }
+        for (idx, label) in self.categories.iter().enumerate() {
+            let room = slot.saturating_sub(1).max(1);
+            let label: Vec<char> = label.chars().take(room).collect();
+            let first = idx * slot + (room - label.len()) / 2;
+            for (c, &b) in label.iter().enumerate() {
+                if first + c < canvas.width {
+                    canvas.set_cell(lines, first + c, b)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw bars growing rightward, category labels being on the left
+    pub fn draw_horizontal_into(
+        &self,
+        canvas: &mut Canvas,
+        mode: BarMode,
+    ) -> Result<(), CanvasError> {
+        if self.categories.is_empty() {
+            return Err(CanvasError::NoData);
+        }
+        let (min, max) = self.range(mode);
+        let label_width = self
+            .categories
+            .iter()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(canvas.width / 4);
+        let first_column = label_width + 1;
+        if first_column + 2 > canvas.width {
+            return Err(CanvasError::OutOfRange("canvas too narrow".into()));
+        }
+        let columns = canvas.width - first_column;
+        let column =
+            |v: f64| first_column + get_cell(v, min, max, columns).expect("value is in range");
+        let slot = (canvas.height / self.categories.len()).max(1);
+
+        for (idx, bars) in self.bars(slot, mode).into_iter().enumerate() {
+            for Bar {
+                symbole,
+                color,
+                offset,
+                thickness,
+                start,
+                end,
+            } in bars
+            {
+                // the column of the start value belongs to the previous bar
+                let (s, e) = (column(start), column(end));
+                let columns = if end > start {
+                    (s + 1).min(e)..=e
+                } else {
+                    e..=s.saturating_sub(1).max(e)
+                };
+                let first = idx * slot + offset;
+                canvas.set_pen(color);
+                for l in first..(first + thickness).min(canvas.height) {
+                    for c in columns.clone() {
+                        canvas.set_cell(l, c, symbole)?;
+                    }
+                }
+            }
+        }
+        canvas.set_pen(None);
+        let zero = column(0.0);
+        for l in 0..canvas.height {
+            canvas.set_cell(l, zero, '│')?;
+        }
+        for (idx, label) in self.categories.iter().enumerate() {
+            let line = idx * slot + slot.saturating_sub(1) / 2;
+            if line >= canvas.height {
+                break;
+            }
+            for (c, b) in label.chars().take(label_width).enumerate() {
+                canvas.set_cell(line, c, b)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bars() -> Bars {
+        let mut bars = Bars::new(vec!["api".into(), "db".into()]);
+        bars.add_series('a', vec![2.0, 4.0]);
+        bars.add_series('b', vec![2.0, f64::NAN]);
+        bars
+    }
+
+    #[test]
+    fn bar_range() {
+        assert_eq!(bars().range(BarMode::Grouped), (0.0, 4.0));
+        assert_eq!(bars().range(BarMode::Stacked), (0.0, 4.0));
+        let mut b = Bars::new(vec!["x".into()]);
+        b.add_series('a', vec![3.0]);
+        b.add_series('b', vec![2.0]);
+        b.add_series('c', vec![-1.0]);
+        assert_eq!(b.range(BarMode::Stacked), (-1.0, 5.0));
+    }
+
+    #[test]
+    fn bar_grouped() {
+        let mut canvas = Canvas::from_size(6, 10);
+        bars().draw_into(&mut canvas, BarMode::Grouped).unwrap();
+        assert_eq!(
+            canvas.to_string(),
+            [
+                "     aa   ",
+                "     aa   ",
+                "aabb aa   ",
+                "aabb aa   ",
+                "──────────",
+                "api   db  ",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn bar_stacked_horizontal() {
+        let mut canvas = Canvas::from_size(4, 13);
+        bars()
+            .draw_horizontal_into(&mut canvas, BarMode::Stacked)
+            .unwrap();
+        assert_eq!(
+            canvas.to_string(),
+            [
+                "api │aaaabbbb",
+                "    │        ",
+                "db  │aaaaaaaa",
+                "    │        ",
+                ""
+            ]
+            .join("\n")
+        );
+    }
+}
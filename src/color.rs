@@ -0,0 +1,200 @@
+use clap::ValueEnum;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Characters from the lowest to the highest density
+pub(crate) const RAMP: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Stops of the color gradient, from the lowest to the highest density
+const GRADIENT: [(u8, u8, u8); 5] = [
+    (68, 1, 84),
+    (59, 82, 139),
+    (33, 145, 140),
+    (94, 201, 98),
+    (253, 231, 37),
+];
+
+/// Terminal color
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Color {
+    /// Color of the 256 colors palette
+    Ansi256(u8),
+
+    /// 24 bits color
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Escape sequence setting this color as foreground or background
+    pub(crate) fn escape(&self, background: bool) -> String {
+        let layer = if background { 48 } else { 38 };
+        match self {
+            Color::Ansi256(c) => format!("\x1b[{layer};5;{c}m"),
+            Color::Rgb(r, g, b) => format!("\x1b[{layer};2;{r};{g};{b}m"),
+        }
+    }
+
+    /// Red, green and blue channels, the 256 colors palette being the xterm one
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        const BASIC: [(u8, u8, u8); 16] = [
+            (0, 0, 0),
+            (205, 0, 0),
+            (0, 205, 0),
+            (205, 205, 0),
+            (0, 0, 238),
+            (205, 0, 205),
+            (0, 205, 205),
+            (229, 229, 229),
+            (127, 127, 127),
+            (255, 0, 0),
+            (0, 255, 0),
+            (255, 255, 0),
+            (92, 92, 255),
+            (255, 0, 255),
+            (0, 255, 255),
+            (255, 255, 255),
+        ];
+        match *self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Ansi256(c @ 0..=15) => BASIC[c as usize],
+            Color::Ansi256(c @ 16..=231) => {
+                let level = |i: u8| if i == 0 { 0 } else { 55 + 40 * i };
+                let c = c - 16;
+                (level(c / 36), level(c / 6 % 6), level(c % 6))
+            }
+            Color::Ansi256(c) => {
+                let gray = 8 + 10 * (c - 232);
+                (gray, gray, gray)
+            }
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Name of one of the 16 basic colors (`red`, `brightblue`...), index of
+    /// the 256 colors palette, or `#rrggbb`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid color '{s}': expected a name, 0-255 or #rrggbb");
+        if let Some(hex) = s.strip_prefix('#') {
+            if hex.len() != 6 || !hex.is_ascii() {
+                return Err(invalid());
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+            return Ok(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+        if let Ok(index) = s.parse() {
+            return Ok(Color::Ansi256(index));
+        }
+        let name = s.to_lowercase();
+        let (bright, name) = match name.strip_prefix("bright") {
+            Some(name) => (8, name),
+            None => (0, name.as_str()),
+        };
+        let index = match name {
+            "black" => 0,
+            "red" => 1,
+            "green" => 2,
+            "yellow" => 3,
+            "blue" => 4,
+            "magenta" => 5,
+            "cyan" => 6,
+            "white" => 7,
+            "gray" | "grey" if bright == 0 => return Ok(Color::Ansi256(8)),
+            _ => return Err(invalid()),
+        };
+        Ok(Color::Ansi256(bright + index))
+    }
+}
+
+/// How densities are rendered
+#[derive(Debug, Default, Copy, Clone, PartialEq, ValueEnum)]
+pub enum Shading {
+    /// Characters of increasing density
+    #[default]
+    Ascii,
+
+    /// 256 colors palette
+    Ansi256,
+
+    /// 24 bits colors
+    Truecolor,
+}
+
+impl Shading {
+    /// Color at a specific level of the gradient, between 0 and 1
+    ///
+    /// Return None in ASCII mode
+    pub fn color(&self, level: f64) -> Option<Color> {
+        let level = level.clamp(0.0, 1.0) * (GRADIENT.len() - 1) as f64;
+        let idx = (level.floor() as usize).min(GRADIENT.len() - 2);
+        let f = level - idx as f64;
+        let (a, b) = (GRADIENT[idx], GRADIENT[idx + 1]);
+        let mix = |a: u8, b: u8| ((1.0 - f) * a as f64 + f * b as f64).round() as u8;
+        let (r, g, b) = (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2));
+        match self {
+            Shading::Ascii => None,
+            Shading::Truecolor => Some(Color::Rgb(r, g, b)),
+            Shading::Ansi256 => {
+                // nearest color of the 6x6x6 color cube
+                let cube = |c: u8| ((c as f64 / 255.0) * 5.0).round() as u8;
+                Some(Color::Ansi256(16 + 36 * cube(r) + 6 * cube(g) + cube(b)))
+            }
+        }
+    }
+
+    /// Character for a specific count, given the highest count
+    pub fn symbole(count: usize, max: usize) -> char {
+        if count == 0 || max == 0 {
+            return RAMP[0];
+        }
+        let levels = RAMP.len() - 1;
+        RAMP[((count * levels).div_ceil(max)).clamp(1, levels)]
+    }
+}
+
+impl Display for Shading {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Shading::Ascii => write!(f, "ascii"),
+            Shading::Ansi256 => write!(f, "ansi256"),
+            Shading::Truecolor => write!(f, "truecolor"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramp() {
+        assert_eq!(Shading::symbole(0, 10), ' ');
+        assert_eq!(Shading::symbole(1, 1000), '.');
+        assert_eq!(Shading::symbole(500, 1000), '+');
+        assert_eq!(Shading::symbole(1000, 1000), '@');
+    }
+
+    #[test]
+    fn color_parse() {
+        assert_eq!("red".parse(), Ok(Color::Ansi256(1)));
+        assert_eq!("BrightBlue".parse(), Ok(Color::Ansi256(12)));
+        assert_eq!("208".parse(), Ok(Color::Ansi256(208)));
+        assert_eq!("#00ff7f".parse(), Ok(Color::Rgb(0, 255, 127)));
+        assert!("#0f0".parse::<Color>().is_err());
+        assert!("mauve".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn gradient() {
+        assert_eq!(Shading::Ascii.color(0.5), None);
+        assert_eq!(Shading::Truecolor.color(0.0), Some(Color::Rgb(68, 1, 84)));
+        assert_eq!(
+            Shading::Truecolor.color(2.0),
+            Some(Color::Rgb(253, 231, 37))
+        );
+        assert_eq!(Shading::Ansi256.color(1.0), Some(Color::Ansi256(227)));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{Canvas, CanvasError, get_mean_std_dev, get_quantile};
+use clap::ValueEnum;
+use std::f64::consts::PI;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Kernel used to smooth each sample
+#[derive(Debug, Default, Copy, Clone, PartialEq, ValueEnum)]
+pub enum Kernel {
+    /// Standard normal distribution
+    #[default]
+    Gaussian,
+
+    /// Parabola, null outside of [-1, 1]
+    Epanechnikov,
+}
+
+impl Kernel {
+    fn get_value(&self, u: f64) -> f64 {
+        match self {
+            Kernel::Gaussian => (-0.5 * u * u).exp() / (2.0 * PI).sqrt(),
+            Kernel::Epanechnikov => {
+                if u.abs() <= 1.0 {
+                    0.75 * (1.0 - u * u)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Distance, in bandwidths, beyond which the kernel is negligible
+    fn support(&self) -> f64 {
+        match self {
+            Kernel::Gaussian => 8.0,
+            Kernel::Epanechnikov => 1.0,
+        }
+    }
+}
+
+impl Display for Kernel {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Kernel::Gaussian => write!(f, "gaussian"),
+            Kernel::Epanechnikov => write!(f, "epanechnikov"),
+        }
+    }
+}
+
+/// How to choose the kernel bandwidth
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Bandwidth {
+    /// Silverman's rule of thumb
+    #[default]
+    Silverman,
+
+    /// Scott's rule of thumb
+    Scott,
+
+    /// User supplied bandwidth
+    Fixed(f64),
+}
+
+impl Bandwidth {
+    /// Compute the bandwidth for sorted, non empty values
+    fn compute(&self, sorted: &[f64]) -> f64 {
+        let n = sorted.len() as f64;
+        let (_, std_dev) = get_mean_std_dev(sorted.iter().copied());
+        let h = match self {
+            Bandwidth::Fixed(h) => return *h,
+            Bandwidth::Scott => 1.06 * std_dev * n.powf(-0.2),
+            Bandwidth::Silverman => {
+                let iqr = get_quantile(sorted, 0.75) - get_quantile(sorted, 0.25);
+                let spread = if iqr > 0.0 {
+                    std_dev.min(iqr / 1.34)
+                } else {
+                    std_dev
+                };
+                0.9 * spread * n.powf(-0.2)
+            }
+        };
+        // constant input, any bandwidth is as good as another
+        if h > 0.0 { h } else { 1.0 }
+    }
+}
+
+impl Display for Bandwidth {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Bandwidth::Silverman => write!(f, "silverman"),
+            Bandwidth::Scott => write!(f, "scott"),
+            Bandwidth::Fixed(h) => write!(f, "{h}"),
+        }
+    }
+}
+
+impl FromStr for Bandwidth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "silverman" => Ok(Bandwidth::Silverman),
+            "scott" => Ok(Bandwidth::Scott),
+            other => match other.parse::<f64>() {
+                Ok(h) if h > 0.0 => Ok(Bandwidth::Fixed(h)),
+                _ => Err(format!(
+                    "invalid bandwidth '{s}': expected silverman, scott or a positive number"
+                )),
+            },
+        }
+    }
+}
+
+/// Kernel density estimate
+#[derive(Debug, Default)]
+pub struct Density {
+    /// Sorted samples
+    values: Vec<f64>,
+    bandwidth: f64,
+    kernel: Kernel,
+}
+
+impl Density {
+    pub fn from_vec(input: Vec<f64>, kernel: Kernel, bandwidth: Bandwidth) -> Self {
+        let mut values: Vec<f64> = input.into_iter().filter(|x| !x.is_nan()).collect();
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Self {
+            bandwidth: bandwidth.compute(&values),
+            values,
+            kernel,
+        }
+    }
+
+    pub fn bandwidth(&self) -> f64 {
+        self.bandwidth
+    }
+
+    /// Range outside of which the density is negligible
+    pub fn range(&self) -> Option<(f64, f64)> {
+        let margin = self.kernel.support().min(3.0) * self.bandwidth;
+        Some((self.values.first()? - margin, self.values.last()? + margin))
+    }
+
+    /// Get the estimated density at x
+    pub fn get_value(&self, x: f64) -> f64 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+        let h = self.bandwidth;
+        let radius = self.kernel.support() * h;
+        let start = self.values.partition_point(|&v| v < x - radius);
+        let end = self.values.partition_point(|&v| v <= x + radius);
+        let sum: f64 = self.values[start..end]
+            .iter()
+            .map(|v| self.kernel.get_value((x - v) / h))
+            .sum();
+        sum / (self.values.len() as f64 * h)
+    }
+
+    /// Draw the density as a curve
+    pub fn draw_into(&self, canvas: &mut Canvas, symbole: char) -> Result<(), CanvasError> {
+        canvas.draw_curve(|x| self.get_value(x), symbole)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bandwidth_parse() {
+        assert_eq!("Scott".parse(), Ok(Bandwidth::Scott));
+        assert_eq!("0.5".parse(), Ok(Bandwidth::Fixed(0.5)));
+        assert!("-1".parse::<Bandwidth>().is_err());
+        assert!("wide".parse::<Bandwidth>().is_err());
+    }
+
+    #[test]
+    fn density_integral() {
+        let values: Vec<f64> = (0..100).map(|x| (x as f64).sqrt()).collect();
+        for kernel in [Kernel::Gaussian, Kernel::Epanechnikov] {
+            let d = Density::from_vec(values.clone(), kernel, Bandwidth::default());
+            let (start, end) = d.range().unwrap();
+            let step = (end - start) / 1000.0;
+            let integral: f64 = (0..1000)
+                .map(|i| d.get_value(start + (i as f64 + 0.5) * step) * step)
+                .sum();
+            assert!((integral - 1.0).abs() < 0.01, "{kernel}: {integral}");
+        }
+    }
+
+    #[test]
+    fn density_fixed() {
+        let d = Density::from_vec(vec![0.0], Kernel::Epanechnikov, Bandwidth::Fixed(2.0));
+        assert_eq!(d.bandwidth(), 2.0);
+        assert_eq!(d.get_value(0.0), 0.375);
+        assert_eq!(d.get_value(3.0), 0.0);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::get_cell;
+use clap::ValueEnum;
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// How large datasets are reduced before drawing points
+#[derive(Debug, Default, Copy, Clone, PartialEq, ValueEnum)]
+pub enum Downsampling {
+    /// Every point is drawn
+    #[default]
+    Off,
+
+    /// Lowest and highest points of each column
+    MinMax,
+
+    /// Largest triangle three buckets, two points per column
+    Lttb,
+}
+
+impl Display for Downsampling {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Downsampling::Off => write!(f, "off"),
+            Downsampling::MinMax => write!(f, "min-max"),
+            Downsampling::Lttb => write!(f, "lttb"),
+        }
+    }
+}
+
+impl Downsampling {
+    /// Points to draw on a specific number of columns covering x_range
+    ///
+    /// NaN and points out of x_range are dropped when downsampling
+    pub fn apply<'a>(
+        &self,
+        points: &'a [(f64, f64)],
+        x_range: (f64, f64),
+        columns: usize,
+    ) -> Cow<'a, [(f64, f64)]> {
+        match self {
+            Downsampling::Off => Cow::Borrowed(points),
+            Downsampling::MinMax => Cow::Owned(min_max(points, x_range, columns)),
+            Downsampling::Lttb => {
+                let mut points: Vec<(f64, f64)> = points
+                    .iter()
+                    .copied()
+                    .filter(|&(x, y)| !y.is_nan() && x >= x_range.0 && x <= x_range.1)
+                    .collect();
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                Cow::Owned(lttb(&points, 2 * columns))
+            }
+        }
+    }
+}
+
+/// (lowest point, highest point)
+type Extrema = ((f64, f64), (f64, f64));
+
+/// Lowest and highest points of each column, in column order
+fn min_max(points: &[(f64, f64)], x_range: (f64, f64), columns: usize) -> Vec<(f64, f64)> {
+    let mut extrema: Vec<Option<Extrema>> = vec![None; columns];
+    for &(x, y) in points {
+        let Ok(column) = get_cell(x, x_range.0, x_range.1, columns) else {
+            continue;
+        };
+        if y.is_nan() {
+            continue;
+        }
+        let (low, high) = extrema[column].get_or_insert(((x, y), (x, y)));
+        if y < low.1 {
+            *low = (x, y);
+        }
+        if y > high.1 {
+            *high = (x, y);
+        }
+    }
+    extrema
+        .into_iter()
+        .flatten()
+        .flat_map(|(low, high)| {
+            if low == high {
+                vec![low]
+            } else {
+                vec![low, high]
+            }
+        })
+        .collect()
+}
+
+/// Largest triangle three buckets, on points sorted by x
+///
+/// Keep the first and last points, and in each bucket between them the point
+/// forming the largest triangle with the previously kept point and the
+/// average of the next bucket
+fn lttb(points: &[(f64, f64)], threshold: usize) -> Vec<(f64, f64)> {
+    let n = points.len();
+    if threshold >= n || threshold < 3 {
+        return points.to_vec();
+    }
+    let bucket = (n - 2) as f64 / (threshold - 2) as f64;
+    let bounds = |i: usize| {
+        let start = 1 + (i as f64 * bucket) as usize;
+        (start, (1 + ((i + 1) as f64 * bucket) as usize).min(n - 1))
+    };
+    let mut sampled = Vec::with_capacity(threshold);
+    sampled.push(points[0]);
+    let mut previous = points[0];
+    for i in 0..threshold - 2 {
+        let (start, end) = bounds(i);
+        let next = if i + 1 < threshold - 2 {
+            let (s, e) = bounds(i + 1);
+            &points[s..e]
+        } else {
+            &points[n - 1..]
+        };
+        let average = next
+            .iter()
+            .fold((0.0, 0.0), |acc, p| (acc.0 + p.0, acc.1 + p.1));
+        let average = (average.0 / next.len() as f64, average.1 / next.len() as f64);
+        let area = |p: &(f64, f64)| {
+            ((previous.0 - average.0) * (p.1 - previous.1)
+                - (previous.0 - p.0) * (average.1 - previous.1))
+                .abs()
+        };
+        if let Some(&kept) = points[start..end]
+            .iter()
+            .max_by(|a, b| area(a).total_cmp(&area(b)))
+        {
+            sampled.push(kept);
+            previous = kept;
+        }
+    }
+    sampled.push(points[n - 1]);
+    sampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_max_columns() {
+        let points = [(0.0, 1.0), (0.1, 5.0), (0.2, -1.0), (9.0, 2.0), (20.0, 0.0)];
+        assert_eq!(
+            Downsampling::MinMax.apply(&points, (0.0, 10.0), 5).as_ref(),
+            [(0.2, -1.0), (0.1, 5.0), (9.0, 2.0)]
+        );
+        assert_eq!(
+            Downsampling::Off.apply(&points, (0.0, 10.0), 5).len(),
+            points.len()
+        );
+    }
+
+    #[test]
+    fn lttb_peaks() {
+        let mut points: Vec<(f64, f64)> = (0..1000).map(|x| (x as f64, 0.0)).collect();
+        points[500].1 = 10.0;
+        points[700].1 = -10.0;
+        let sampled = Downsampling::Lttb.apply(&points, (0.0, 999.0), 5);
+        assert_eq!(sampled.len(), 10);
+        assert_eq!(sampled[0], (0.0, 0.0));
+        assert_eq!(sampled[9], (999.0, 0.0));
+        assert!(sampled.contains(&(500.0, 10.0)));
+        assert!(sampled.contains(&(700.0, -10.0)));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{Canvas, CanvasError};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Columns holding the uncertainty of a value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorSpec {
+    /// Same deviation below and above the value
+    Symmetric(String),
+
+    /// Deviations below and above the value
+    Asymmetric(String, String),
+
+    /// Lower and upper bounds of the interval
+    Bounds(String, String),
+}
+
+impl ErrorSpec {
+    /// Labels of the columns used
+    pub fn columns(&self) -> Vec<&String> {
+        match self {
+            ErrorSpec::Symmetric(c) => vec![c],
+            ErrorSpec::Asymmetric(a, b) | ErrorSpec::Bounds(a, b) => vec![a, b],
+        }
+    }
+
+    /// Interval around a value, given the values of the columns
+    pub fn interval(&self, value: f64, columns: &[f64]) -> (f64, f64) {
+        match self {
+            ErrorSpec::Symmetric(_) => (value - columns[0], value + columns[0]),
+            ErrorSpec::Asymmetric(_, _) => (value - columns[0], value + columns[1]),
+            ErrorSpec::Bounds(_, _) => (columns[0], columns[1]),
+        }
+    }
+}
+
+impl Display for ErrorSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ErrorSpec::Symmetric(c) => write!(f, "{c}"),
+            ErrorSpec::Asymmetric(minus, plus) => write!(f, "{minus}/{plus}"),
+            ErrorSpec::Bounds(low, high) => write!(f, "{low}..{high}"),
+        }
+    }
+}
+
+/// Association of a dataset with the columns of its uncertainty
+///
+/// Written `label=error` (symmetric), `label=minus/plus` (asymmetric)
+/// or `label=low..high` (bounds)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorMapping {
+    pub label: String,
+    pub spec: ErrorSpec,
+}
+
+impl Display for ErrorMapping {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.label, self.spec)
+    }
+}
+
+impl FromStr for ErrorMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid error mapping '{s}': expected label=error, label=minus/plus or label=low..high"
+            )
+        };
+        let (label, spec) = s.split_once('=').ok_or_else(invalid)?;
+        let spec = if let Some((low, high)) = spec.split_once("..") {
+            ErrorSpec::Bounds(low.into(), high.into())
+        } else if let Some((minus, plus)) = spec.split_once('/') {
+            ErrorSpec::Asymmetric(minus.into(), plus.into())
+        } else {
+            ErrorSpec::Symmetric(spec.into())
+        };
+        if label.is_empty() || spec.columns().iter().any(|c| c.is_empty()) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            label: label.into(),
+            spec,
+        })
+    }
+}
+
+/// Uncertainty of a point, as intervals along x and y
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ErrorBar {
+    pub x: Option<(f64, f64)>,
+    pub y: Option<(f64, f64)>,
+}
+
+impl ErrorBar {
+    /// Draw the intervals around a point
+    pub fn draw_into(&self, canvas: &mut Canvas, point: (f64, f64)) -> Result<(), CanvasError> {
+        if point.0.is_nan() || point.1.is_nan() {
+            return Ok(());
+        }
+        if let Some((low, high)) = self.y.filter(|i| !i.0.is_nan() && !i.1.is_nan()) {
+            canvas.draw_vline(point.0, low, high, '|')?;
+        }
+        if let Some((low, high)) = self.x.filter(|i| !i.0.is_nan() && !i.1.is_nan()) {
+            canvas.draw_hline(point.1, low, high, '-')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_parse() {
+        let m: ErrorMapping = "mean=sd".parse().unwrap();
+        assert_eq!(m.spec, ErrorSpec::Symmetric("sd".into()));
+        let m: ErrorMapping = "mean=lo..hi".parse().unwrap();
+        assert_eq!(m.spec, ErrorSpec::Bounds("lo".into(), "hi".into()));
+        assert_eq!(m.spec.interval(1.0, &[0.5, 3.0]), (0.5, 3.0));
+        let m: ErrorMapping = "mean=m/p".parse().unwrap();
+        assert_eq!(m.spec.interval(1.0, &[0.5, 3.0]), (0.5, 4.0));
+        assert!("mean".parse::<ErrorMapping>().is_err());
+        assert!("mean=a/".parse::<ErrorMapping>().is_err());
+    }
+
+    #[test]
+    fn error_bar_draw() {
+        let mut canvas = Canvas::from_size(10, 10);
+        canvas.set_x_range(0.0, 10.0);
+        canvas.set_y_range(0.0, 10.0);
+        let bar = ErrorBar {
+            x: Some((2.0, 8.0)),
+            y: Some((0.0, 10.0)),
+        };
+        bar.draw_into(&mut canvas, (5.0, 5.0)).unwrap();
+        let text = canvas.to_string();
+        assert_eq!(text.matches('|').count(), 7);
+        assert_eq!(text.matches('-').count(), 6);
+    }
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::Duration;
+
+/// Points kept when data keeps arriving
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Window {
+    /// Last points of each dataset
+    Points(usize),
+
+    /// Points whose x is within a specific span of the highest x,
+    /// in seconds for dates
+    Span(f64),
+}
+
+impl Display for Window {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Window::Points(n) => write!(f, "{n}"),
+            Window::Span(span) => write!(f, "{span}s"),
+        }
+    }
+}
+
+impl FromStr for Window {
+    type Err = String;
+
+    /// A number of points (`500`), or a span with a unit:
+    /// `s`, `m`, `h` or `d` (`30s`, `5m`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid window '{s}': expected N or a span such as 30s, 5m, 2h");
+        if let Ok(n) = s.parse() {
+            return Ok(Window::Points(n));
+        }
+        let unit = match s.chars().last() {
+            Some('s') => 1.0,
+            Some('m') => 60.0,
+            Some('h') => 3600.0,
+            Some('d') => 86400.0,
+            _ => return Err(invalid()),
+        };
+        let span: f64 = s[..s.len() - 1].parse().map_err(|_| invalid())?;
+        if span.is_nan() || span < 0.0 {
+            return Err(invalid());
+        }
+        Ok(Window::Span(span * unit))
+    }
+}
+
+/// Send the complete lines of a reader as they are written
+///
+/// At the end of the input, the reader is polled again every poll interval,
+/// or the channel is closed if there is no poll interval (e.g. for stdin)
+pub fn tail_lines(
+    mut reader: impl BufRead + Send + 'static,
+    poll: Option<Duration>,
+) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut line = String::new();
+        loop {
+            match reader.read_line(&mut line) {
+                // a partial line is completed by the next reads
+                Ok(_) if line.ends_with('\n') => {
+                    if sender.send(line.trim_end().to_string()).is_err() {
+                        return;
+                    }
+                    line.clear();
+                }
+                Ok(_) => match poll {
+                    Some(poll) => thread::sleep(poll),
+                    None => {
+                        if !line.is_empty() {
+                            let _ = sender.send(line);
+                        }
+                        return;
+                    }
+                },
+                Err(_) => return,
+            }
+        }
+    });
+    receiver
+}
+
+/// Replace a frame of previous_lines lines, written last, by a new one
+///
+/// Return the number of lines of the new frame
+pub fn redraw(out: &mut impl Write, previous_lines: usize, frame: &str) -> io::Result<usize> {
+    if previous_lines > 0 {
+        // beginning of the first line of the previous frame, then clear below
+        write!(out, "\x1b[{previous_lines}F\x1b[J")?;
+    }
+    write!(out, "{frame}")?;
+    out.flush()?;
+    Ok(frame.matches('\n').count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_parse() {
+        assert_eq!("500".parse(), Ok(Window::Points(500)));
+        assert_eq!("30s".parse(), Ok(Window::Span(30.0)));
+        assert_eq!("1.5h".parse(), Ok(Window::Span(5400.0)));
+        assert!("5y".parse::<Window>().is_err());
+        assert!("-5m".parse::<Window>().is_err());
+    }
+
+    #[test]
+    fn tail() {
+        let lines = tail_lines("x,a\n0,1\n1,2".as_bytes(), None);
+        assert_eq!(lines.iter().collect::<Vec<_>>(), ["x,a", "0,1", "1,2"]);
+    }
+
+    #[test]
+    fn redraw_frames() {
+        let mut out = Vec::new();
+        let lines = redraw(&mut out, 0, "a\nb\n").unwrap();
+        redraw(&mut out, lines, "c\n").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "a\nb\n\x1b[2F\x1b[Jc\n");
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{Canvas, CanvasError, DataSet};
+
+impl Canvas {
+    /// Draw dotted lines at the ticks of the last axes drawn, under previous drawings
+    pub(crate) fn draw_grid(&mut self) -> Result<(), CanvasError> {
+        let Some((x_axis_location, y_axis_location)) = self.origin else {
+            return Ok(());
+        };
+        for line in 0..self.height {
+            let tick = (line as i32 - x_axis_location as i32) % 5 == 0;
+            for column in 0..self.width {
+                if tick || (column as i32 - y_axis_location as i32) % 5 == 0 {
+                    self.set_empty_cell(line, column, '·')?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DataSet {
+    /// Draw grid lines at the ticks of the axes, behind the data
+    pub fn set_grid(&mut self, grid: bool) {
+        self.grid = grid;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlotKind;
+
+    #[test]
+    fn grid_lines() {
+        let mut dataset = DataSet::from_csv("x,a\n0,0\n10,10\n").unwrap();
+        dataset.set_grid(true);
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        let text = canvas.to_string();
+        let (x_axis, y_axis) = canvas.origin.unwrap();
+        let tick = |cell: usize, axis: usize| (cell as i32 - axis as i32) % 5 == 0;
+        for (line, cells) in text.lines().enumerate().filter(|(l, _)| *l != x_axis) {
+            for (column, c) in cells.chars().enumerate().filter(|(c, _)| *c != y_axis) {
+                let expected = if tick(line, x_axis) || tick(column, y_axis) {
+                    '·'
+                } else {
+                    ' '
+                };
+                assert!(c == expected || c == 'a', "{line}, {column}: {c}");
+            }
+        }
+        assert_eq!(text.matches('a').count(), 2);
+
+        // no axes, no grid
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Bar).unwrap();
+        assert!(!canvas.to_string().contains('·'));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{Canvas, CanvasError, Shading, get_cell};
+
+/// Number of points in each cell of the canvas
+#[derive(Debug, Default)]
+pub struct Heatmap {
+    /// Vec<line: Vec<count>>
+    counts: Vec<Vec<usize>>,
+}
+
+impl Heatmap {
+    /// Bin points according to the ranges of the canvas
+    ///
+    /// Points outside of the canvas and NaN are ignored
+    pub fn from_points<'a>(
+        points: impl IntoIterator<Item = &'a (f64, f64)>,
+        canvas: &Canvas,
+    ) -> Self {
+        let mut counts = vec![vec![0; canvas.width]; canvas.height];
+        for &(x, y) in points {
+            if x.is_nan() || y.is_nan() {
+                continue;
+            }
+            let (Ok(line), Ok(column)) = (
+                canvas.get_line(y),
+                get_cell(x, canvas.x_range.0, canvas.x_range.1, canvas.width),
+            ) else {
+                continue;
+            };
+            if let Some(count) = counts.get_mut(line).and_then(|l| l.get_mut(column)) {
+                *count += 1;
+            }
+        }
+        Self { counts }
+    }
+
+    /// Highest count of a cell
+    pub fn max(&self) -> usize {
+        self.counts.iter().flatten().copied().max().unwrap_or(0)
+    }
+
+    /// Shade the cells, the last line of the canvas holding the color scale
+    pub fn draw_into(&self, canvas: &mut Canvas, shading: Shading) -> Result<(), CanvasError> {
+        let max = self.max();
+        for (line, counts) in self.counts.iter().enumerate() {
+            for (column, &count) in counts.iter().enumerate() {
+                if count == 0 {
+                    continue;
+                }
+                match shading.color(count as f64 / max as f64) {
+                    Some(color) => canvas.set_background(line, column, color)?,
+                    None => canvas.set_cell(line, column, Shading::symbole(count, max))?,
+                }
+            }
+        }
+        self.draw_legend(canvas, shading)
+    }
+
+    /// Draw the color scale with the lowest and highest counts on the last line
+    fn draw_legend(&self, canvas: &mut Canvas, shading: Shading) -> Result<(), CanvasError> {
+        let line = canvas.height - 1;
+        canvas.draw_text(line, 0, &" ".repeat(canvas.width))?;
+        let max = self.max();
+        let low = format!("count: {} ", max.min(1));
+        let high = format!(" {max}");
+        let steps = 20;
+        if low.len() + steps + high.len() > canvas.width {
+            return Ok(());
+        }
+        canvas.draw_text(line, 0, &low)?;
+        for step in 0..steps {
+            let column = low.len() + step;
+            let level = (step + 1) as f64 / steps as f64;
+            match shading.color(level) {
+                Some(color) => canvas.set_background(line, column, color)?,
+                None => canvas.set_cell(
+                    line,
+                    column,
+                    Shading::symbole((level * max as f64).ceil() as usize, max),
+                )?,
+            }
+        }
+        canvas.draw_text(line, low.len() + steps, &high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heatmap_counts() {
+        let mut canvas = Canvas::from_size(10, 40);
+        canvas.set_x_range(0.0, 1.0);
+        canvas.set_y_range(0.0, 1.0);
+        let mut points = vec![(0.0, 0.0); 3];
+        points.push((1.0, 1.0));
+        points.push((f64::NAN, 1.0));
+        points.push((2.0, 1.0));
+        let heatmap = Heatmap::from_points(&points, &canvas);
+        assert_eq!(heatmap.max(), 3);
+        assert_eq!(heatmap.counts.iter().flatten().sum::<usize>(), 4);
+
+        heatmap.draw_into(&mut canvas, Shading::Ascii).unwrap();
+        let text = canvas.to_string();
+        let (plot, legend) = text.trim_end().rsplit_once('\n').unwrap();
+        assert_eq!(plot.matches('@').count(), 1);
+        assert_eq!(plot.matches('-').count(), 1);
+        assert!(legend.starts_with("count: 1 "));
+        assert!(legend.ends_with(" 3"));
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{Canvas, DataSet, PlotKind};
+use clap::ValueEnum;
+use std::collections::BTreeSet;
+
+/// Fraction of the ranges moved by each pan
+const PAN: f64 = 0.1;
+
+/// Factor applied to the ranges by each zoom
+const ZOOM: f64 = 1.5;
+
+/// Action requested in the interactive viewer
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    ZoomIn,
+    ZoomOut,
+    /// Next plot kind
+    NextKind,
+    /// Select the next dataset
+    NextSeries,
+    /// Hide the selected dataset, or show it again
+    ToggleSeries,
+    /// Ranges fitting the data
+    Reset,
+    Quit,
+}
+
+impl Key {
+    /// Decode raw terminal input, unknown bytes being ignored
+    ///
+    /// Arrows pan, `+`/`-` zoom, `k` cycles plot kinds, `s` selects the next
+    /// dataset, `l` toggles its visibility, `r` resets ranges, `q` or Ctrl-C quits
+    pub fn parse_all(input: &[u8]) -> Vec<Key> {
+        let mut keys = Vec::new();
+        let mut idx = 0;
+        while idx < input.len() {
+            let key = match input[idx..] {
+                // arrows, in normal or application cursor mode
+                [0x1b, b'[' | b'O', arrow, ..] => {
+                    idx += 2;
+                    match arrow {
+                        b'A' => Some(Key::Up),
+                        b'B' => Some(Key::Down),
+                        b'C' => Some(Key::Right),
+                        b'D' => Some(Key::Left),
+                        _ => None,
+                    }
+                }
+                [b'+' | b'=', ..] => Some(Key::ZoomIn),
+                [b'-' | b'_', ..] => Some(Key::ZoomOut),
+                [b'k', ..] => Some(Key::NextKind),
+                [b's', ..] => Some(Key::NextSeries),
+                [b'l', ..] => Some(Key::ToggleSeries),
+                [b'r', ..] => Some(Key::Reset),
+                [b'q' | 0x03, ..] => Some(Key::Quit),
+                _ => None,
+            };
+            keys.extend(key);
+            idx += 1;
+        }
+        keys
+    }
+}
+
+/// State of the interactive viewer, independent of the terminal
+#[derive(Debug)]
+pub struct Viewer {
+    kind: PlotKind,
+    /// Index, among sorted labels, of the selected dataset
+    selected: usize,
+    /// Labels of the datasets not drawn
+    hidden: BTreeSet<String>,
+    /// (x_range, y_range) imposed by panning and zooming
+    view: Option<((f64, f64), (f64, f64))>,
+    /// (x_range, y_range) of the last frame
+    ranges: ((f64, f64), (f64, f64)),
+    /// Sorted labels of the datasets of the last frame
+    labels: Vec<String>,
+    /// Whether the y axis of the last frame is logarithmic
+    log_y: bool,
+}
+
+impl Viewer {
+    pub fn new(kind: PlotKind) -> Self {
+        Self {
+            kind,
+            selected: 0,
+            hidden: BTreeSet::new(),
+            view: None,
+            ranges: ((0.0, 0.0), (0.0, 0.0)),
+            labels: Vec::new(),
+            log_y: false,
+        }
+    }
+
+    pub fn kind(&self) -> PlotKind {
+        self.kind
+    }
+
+    /// Ranges imposed on the plot, None if they fit the data
+    pub fn view(&self) -> Option<((f64, f64), (f64, f64))> {
+        self.view
+    }
+
+    /// Update the state, return false if the viewer should quit
+    pub fn handle(&mut self, key: Key) -> bool {
+        let ((x0, x1), y_range) = self.view.unwrap_or(self.ranges);
+        // logarithmic axes are moved in log space, so that they stay positive
+        let (y0, y1) = if self.log_y {
+            (y_range.0.log10(), y_range.1.log10())
+        } else {
+            y_range
+        };
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let (x_range, y_range) = match key {
+            Key::Left => ((x0 - PAN * dx, x1 - PAN * dx), (y0, y1)),
+            Key::Right => ((x0 + PAN * dx, x1 + PAN * dx), (y0, y1)),
+            Key::Up => ((x0, x1), (y0 + PAN * dy, y1 + PAN * dy)),
+            Key::Down => ((x0, x1), (y0 - PAN * dy, y1 - PAN * dy)),
+            Key::ZoomIn => (zoom(x0, x1, 1.0 / ZOOM), zoom(y0, y1, 1.0 / ZOOM)),
+            Key::ZoomOut => (zoom(x0, x1, ZOOM), zoom(y0, y1, ZOOM)),
+            _ => ((x0, x1), (y0, y1)),
+        };
+        match key {
+            Key::Left | Key::Right | Key::Up | Key::Down | Key::ZoomIn | Key::ZoomOut => {
+                let y_range = if self.log_y {
+                    (10f64.powf(y_range.0), 10f64.powf(y_range.1))
+                } else {
+                    y_range
+                };
+                self.view = Some((x_range, y_range));
+            }
+            Key::NextKind => {
+                let kinds = PlotKind::value_variants();
+                let idx = kinds.iter().position(|&k| k == self.kind).unwrap_or(0);
+                self.kind = kinds[(idx + 1) % kinds.len()];
+                // ranges of different kinds are unrelated
+                self.view = None;
+            }
+            Key::NextSeries => {
+                self.selected = (self.selected + 1) % self.labels.len().max(1);
+            }
+            Key::ToggleSeries => {
+                if let Some(label) = self.labels.get(self.selected)
+                    && !self.hidden.remove(label)
+                {
+                    self.hidden.insert(label.clone());
+                }
+            }
+            Key::Reset => self.view = None,
+            Key::Quit => return false,
+        }
+        true
+    }
+
+    /// Plot of the visible datasets, followed by a status line
+    pub fn draw(&mut self, dataset: &DataSet) -> String {
+        self.labels = dataset.labels().into_iter().cloned().collect();
+        if self.selected >= self.labels.len() {
+            self.selected = 0;
+        }
+        let mut visible = dataset.clone();
+        visible.retain(|l| !self.hidden.contains(l));
+        // selected dataset between brackets, hidden ones between parentheses
+        let series: Vec<String> = self
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(idx, label)| {
+                let label = if self.hidden.contains(label) {
+                    format!("({label})")
+                } else {
+                    label.clone()
+                };
+                if idx == self.selected {
+                    format!("[{label}]")
+                } else {
+                    label
+                }
+            })
+            .collect();
+
+        let mut canvas = Canvas::new();
+        canvas.set_view(self.view);
+        let _ = visible.draw_into(&mut canvas, self.kind);
+        self.ranges = (canvas.x_range(), canvas.y_range());
+        self.log_y = canvas.log_y;
+        format!(
+            "{canvas}{} | {} | arrows pan, +/- zoom, k kind, s select, l toggle, r reset, q quit\n",
+            self.kind,
+            series.join(" "),
+        )
+    }
+}
+
+/// Range scaled by a factor around its center
+fn zoom(min: f64, max: f64, factor: f64) -> (f64, f64) {
+    let (center, half) = ((min + max) / 2.0, (max - min) / 2.0 * factor);
+    (center - half, center + half)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_parse() {
+        assert_eq!(
+            Key::parse_all(b"\x1b[D\x1bOA+-kslrxq"),
+            [
+                Key::Left,
+                Key::Up,
+                Key::ZoomIn,
+                Key::ZoomOut,
+                Key::NextKind,
+                Key::NextSeries,
+                Key::ToggleSeries,
+                Key::Reset,
+                Key::Quit,
+            ]
+        );
+        assert_eq!(Key::parse_all(b"\x03"), [Key::Quit]);
+    }
+
+    #[test]
+    fn viewer_keys() {
+        let dataset = DataSet::from_csv("x,a,b\n0,0,1\n10,10,5\n").unwrap();
+        let mut viewer = Viewer::new(PlotKind::Point);
+        let frame = viewer.draw(&dataset);
+        assert!(frame.lines().last().unwrap().starts_with("point | [a] b |"));
+        let ((x0, x1), _) = viewer.ranges;
+
+        for &key in &Key::parse_all(b"+\x1b[C") {
+            assert!(viewer.handle(key));
+            viewer.draw(&dataset);
+        }
+        let ((v0, v1), _) = viewer.view().unwrap();
+        assert!((v1 - v0 - (x1 - x0) / ZOOM).abs() < 1e-9);
+        assert!(v0 > x0 + (x1 - x0) / 6.0);
+
+        viewer.handle(Key::Reset);
+        assert_eq!(viewer.view(), None);
+        viewer.handle(Key::NextSeries);
+        viewer.handle(Key::ToggleSeries);
+        let frame = viewer.draw(&dataset);
+        let (plot, status) = frame.trim_end().rsplit_once('\n').unwrap();
+        assert!(status.starts_with("point | a [(b)] |"));
+        assert!(plot.contains('a') && !plot.contains('b'));
+        // the selection wraps around, each dataset is toggled independently
+        viewer.handle(Key::NextSeries);
+        viewer.handle(Key::ToggleSeries);
+        let frame = viewer.draw(&dataset);
+        assert!(frame.contains("point | [(a)] (b) |"));
+        viewer.handle(Key::NextSeries);
+        viewer.handle(Key::ToggleSeries);
+        let frame = viewer.draw(&dataset);
+        let (plot, status) = frame.trim_end().rsplit_once('\n').unwrap();
+        assert!(status.starts_with("point | (a) [b] |"));
+        assert!(!plot.contains('a') && plot.contains('b'));
+
+        viewer.handle(Key::NextKind);
+        assert_eq!(viewer.kind(), PlotKind::Boxplot);
+        assert!(!viewer.handle(Key::Quit));
+    }
+
+    #[test]
+    fn viewer_log_axis() {
+        let dataset = DataSet::from_csv(
+            "x,a
+0,1
+1,2
+2,3
+3,4
+",
+        )
+        .unwrap();
+        let mut viewer = Viewer::new(PlotKind::CCDF);
+        viewer.draw(&dataset);
+        for key in [Key::ZoomOut, Key::Down, Key::Down, Key::ZoomOut] {
+            viewer.handle(key);
+            let frame = viewer.draw(&dataset);
+            assert!(frame.contains('a'));
+            let (_, (v0, _)) = viewer.view().unwrap();
+            assert!(v0 > 0.0);
+        }
+        // zoomed by a factor in log space
+        viewer.handle(Key::Reset);
+        viewer.draw(&dataset);
+        let (_, (y0, y1)) = viewer.ranges;
+        viewer.handle(Key::ZoomOut);
+        let (_, (v0, v1)) = viewer.view().unwrap();
+        let ratio = (v1 / v0).log10() / (y1 / y0).log10();
+        assert!((ratio - ZOOM).abs() < 1e-9);
+    }
+}
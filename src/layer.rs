@@ -0,0 +1,204 @@
+use crate::{Canvas, CanvasError, DataSet, PlotKind};
+use clap::ValueEnum;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Plot drawn over others on the same canvas
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    pub kind: PlotKind,
+    /// Labels of the datasets drawn, all of them if empty
+    pub series: Vec<String>,
+    /// Symbol replacing the first letter of the labels
+    pub marker: Option<char>,
+}
+
+impl Layer {
+    /// Layer drawing all datasets with their own symbol
+    pub fn new(kind: PlotKind) -> Self {
+        Self {
+            kind,
+            series: Vec::new(),
+            marker: None,
+        }
+    }
+}
+
+impl Display for Layer {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if !self.series.is_empty() {
+            write!(f, ":{}", self.series.join("+"))?;
+        }
+        if let Some(marker) = self.marker {
+            write!(f, "@{marker}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Layer {
+    type Err = String;
+
+    /// kind[:label+label...][@marker], such as `density`, `point:a+b` or `histogram:a@#`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| format!("invalid layer '{s}': {reason}");
+        let (rest, marker) = match s.rsplit_once('@') {
+            Some((rest, marker)) => {
+                let mut chars = marker.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(marker), None) => (rest, Some(marker)),
+                    _ => return Err(invalid("marker should be a single character")),
+                }
+            }
+            None => (s, None),
+        };
+        let (kind, series) = rest.split_once(':').unwrap_or((rest, ""));
+        let kind = PlotKind::from_str(kind, true).map_err(|_| invalid("unknown plot kind"))?;
+        let series = series
+            .split('+')
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect();
+        Ok(Self {
+            kind,
+            series,
+            marker,
+        })
+    }
+}
+
+impl DataSet {
+    /// Draw several plots on the same canvas, with ranges fitting all of them
+    ///
+    /// Ranges imposed on the canvas (see [Canvas::set_view]) are kept.
+    /// Layers should share units, e.g. a point cloud and its trend. Histograms
+    /// drawn with a density are scaled as densities, and logarithmic y axes
+    /// (ccdf) cannot be mixed with linear ones.
+    pub fn draw_layers(&self, canvas: &mut Canvas, layers: &[Layer]) -> Result<(), CanvasError> {
+        let logarithmic = layers.iter().filter(|l| l.kind == PlotKind::CCDF).count();
+        if logarithmic > 0 && logarithmic < layers.len() {
+            return Err(CanvasError::Unsupported(
+                "layers mixing logarithmic (ccdf) and linear y axes".to_string(),
+            ));
+        }
+        let with_density = layers.iter().any(|l| l.kind == PlotKind::Density);
+        let plots: Vec<(DataSet, PlotKind)> = layers
+            .iter()
+            .map(|layer| {
+                let mut selected = self.select(layer);
+                if with_density {
+                    selected.set_histogram_density(true);
+                }
+                (selected, layer.kind)
+            })
+            .collect();
+        let imposed = canvas.view;
+        if imposed.is_none() {
+            let ranges = plots
+                .iter()
+                .filter_map(|(dataset, kind)| {
+                    let mut scratch = Canvas::from_size(canvas.height, canvas.width);
+                    dataset.draw_into(&mut scratch, *kind).ok()?;
+                    let (x_range, y_range) = (scratch.x_range, scratch.y_range);
+                    (x_range.0 < x_range.1 && y_range.0 < y_range.1).then_some((x_range, y_range))
+                })
+                .reduce(|(x0, y0), (x1, y1)| {
+                    (
+                        (x0.0.min(x1.0), x0.1.max(x1.1)),
+                        (y0.0.min(y1.0), y0.1.max(y1.1)),
+                    )
+                })
+                .ok_or(CanvasError::NoData)?;
+            // margins of the union, so that its extrema stay inside the canvas
+            let mut fitted = Canvas::from_size(canvas.height, canvas.width);
+            fitted.set_x_range(ranges.0.0, ranges.0.1);
+            fitted.set_y_range(ranges.1.0, ranges.1.1);
+            canvas.set_view(Some((fitted.x_range, fitted.y_range)));
+        }
+        let drawn = plots
+            .iter()
+            .map(|(dataset, kind)| dataset.draw_into(canvas, *kind))
+            .collect::<Result<Vec<_>, _>>();
+        canvas.set_view(imposed);
+        drawn.map(|_| ())
+    }
+
+    /// Datasets of a layer, with its marker
+    fn select(&self, layer: &Layer) -> DataSet {
+        let mut selected = self.clone();
+        if !layer.series.is_empty() {
+            selected.retain(|label| layer.series.iter().any(|l| l == label));
+        }
+        if let Some(marker) = layer.marker {
+            for label in self.dataset.keys() {
+                selected.set_marker(label, marker);
+            }
+        }
+        selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layer_parse() {
+        let layer: Layer = "point:a+b@*".parse().unwrap();
+        assert_eq!(layer.kind, PlotKind::Point);
+        assert_eq!(layer.series, ["a", "b"]);
+        assert_eq!(layer.marker, Some('*'));
+        assert_eq!(layer.to_string(), "point:a+b@*");
+        assert_eq!("Density".parse(), Ok(Layer::new(PlotKind::Density)));
+        assert!("pie".parse::<Layer>().is_err());
+        assert!("point@ab".parse::<Layer>().is_err());
+    }
+
+    #[test]
+    fn layers_ranges() {
+        let dataset = DataSet::from_csv("x,a,b\n0,0,100\n1,1,200\n2,2,300\n").unwrap();
+        let mut canvas = Canvas::new();
+        let layers = ["point:a".parse().unwrap(), "point:b@#".parse().unwrap()];
+        dataset.draw_layers(&mut canvas, &layers).unwrap();
+        let text = canvas.to_string();
+        // both layers share the range fitting b
+        assert_eq!(text.matches('a').count(), 3);
+        assert_eq!(text.matches('#').count(), 3);
+        assert!(!text.contains('b'));
+        let line = |c| text.lines().position(|l| l.contains(c)).unwrap();
+        assert!(line('#') < line('a'));
+        assert!(canvas.view.is_none());
+    }
+
+    #[test]
+    fn layers_histogram_density() {
+        let values: Vec<String> = (0..100)
+            .map(|i| format!("{i},{}", (i as f64 / 10.0).sqrt()))
+            .collect();
+        let dataset = DataSet::from_csv(&format!("x,a\n{}\n", values.join("\n"))).unwrap();
+        let mut canvas = Canvas::new();
+        let layers = ["histogram@#".parse().unwrap(), "density@*".parse().unwrap()];
+        dataset.draw_layers(&mut canvas, &layers).unwrap();
+        let text = canvas.to_string();
+        // both reach the upper part of the canvas
+        let highest = |c| text.lines().position(|l| l.contains(c)).unwrap();
+        assert!(highest('#') < 8);
+        assert!(highest('*') < 12);
+    }
+
+    #[test]
+    fn layers_logarithmic() {
+        let dataset = DataSet::from_csv("x,a\n0,1\n1,2\n2,-3\n").unwrap();
+        let mut canvas = Canvas::new();
+        let layers = ["point".parse().unwrap(), "ccdf".parse().unwrap()];
+        assert!(matches!(
+            dataset.draw_layers(&mut canvas, &layers),
+            Err(CanvasError::Unsupported(_))
+        ));
+        let layers = ["ccdf:a".parse().unwrap(), "ccdf@#".parse().unwrap()];
+        dataset.draw_layers(&mut canvas, &layers).unwrap();
+        assert!(canvas.to_string().contains('#'));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{Canvas, CanvasError, DataSet, PlotKind};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Grid of panels
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Layout {
+    pub rows: usize,
+    pub columns: usize,
+}
+
+impl Layout {
+    /// Smallest grid, about as wide as high, holding n panels
+    pub fn fitting(n: usize) -> Self {
+        let columns = (n.max(1) as f64).sqrt().ceil() as usize;
+        Self {
+            rows: n.max(1).div_ceil(columns),
+            columns,
+        }
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.rows, self.columns)
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    /// rows x columns, such as `2x2` or `1x3`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid layout '{s}': expected ROWSxCOLUMNS such as 2x2");
+        let (rows, columns) = s.split_once('x').ok_or_else(invalid)?;
+        let rows: usize = rows.trim().parse().map_err(|_| invalid())?;
+        let columns: usize = columns.trim().parse().map_err(|_| invalid())?;
+        if rows == 0 || columns == 0 {
+            return Err(invalid());
+        }
+        Ok(Self { rows, columns })
+    }
+}
+
+/// Several plots laid out in a grid, row by row
+#[derive(Debug)]
+pub struct Figure {
+    layout: Layout,
+    /// Size of the whole figure
+    height: usize,
+    width: usize,
+    /// (title, data, kind) of each panel
+    panels: Vec<(String, DataSet, PlotKind)>,
+    /// Whether all panels use the union of their x ranges
+    shared_x: bool,
+    /// Whether all panels use the union of their y ranges
+    shared_y: bool,
+}
+
+impl Figure {
+    /// Figure of the size of a canvas
+    pub fn new(layout: Layout) -> Self {
+        Self::with_size(layout, 25, 80)
+    }
+
+    pub fn with_size(layout: Layout, height: usize, width: usize) -> Self {
+        Self {
+            layout,
+            height,
+            width,
+            panels: Vec::new(),
+            shared_x: false,
+            shared_y: false,
+        }
+    }
+
+    /// One panel per dataset, in the order of their labels
+    ///
+    /// The layout is chosen from the number of datasets if not provided,
+    /// rows being added to the one provided if it is too small
+    pub fn facet_by_series(dataset: &DataSet, kind: PlotKind, layout: Option<Layout>) -> Self {
+        let labels = dataset.labels();
+        let layout = match layout {
+            Some(layout) => Layout {
+                rows: layout.rows.max(labels.len().div_ceil(layout.columns)),
+                ..layout
+            },
+            None => Layout::fitting(labels.len()),
+        };
+        let mut figure = Self::new(layout);
+        for label in labels {
+            let mut series = dataset.clone();
+            series.retain(|l| l == label);
+            figure
+                .add_panel(label.clone(), series, kind)
+                .expect("layout should hold all datasets");
+        }
+        figure
+    }
+
+    /// Add a panel in the next free cell of the grid
+    pub fn add_panel(
+        &mut self,
+        title: impl Into<String>,
+        dataset: DataSet,
+        kind: PlotKind,
+    ) -> Result<(), CanvasError> {
+        if self.panels.len() >= self.layout.rows * self.layout.columns {
+            return Err(CanvasError::OutOfRange(format!(
+                "layout {} is full",
+                self.layout
+            )));
+        }
+        self.panels.push((title.into(), dataset, kind));
+        Ok(())
+    }
+
+    /// Whether panels share their x and y ranges, independent by default
+    pub fn share_axes(&mut self, x: bool, y: bool) {
+        self.shared_x = x;
+        self.shared_y = y;
+    }
+
+    /// Size of the canvas of each panel, a line being kept for its title
+    /// and a column for separators
+    fn panel_size(&self) -> (usize, usize) {
+        let height = (self.height / self.layout.rows).max(4) - 1;
+        let width = ((self.width + 1) / self.layout.columns).max(4) - 1;
+        (height, width)
+    }
+
+    /// Draw each panel, with shared ranges if needed
+    fn draw_panels(&self) -> Vec<Canvas> {
+        let canvases: Vec<Canvas> = self
+            .panels
+            .iter()
+            .map(|(_, dataset, kind)| self.draw_panel(dataset, *kind, None))
+            .collect();
+        if !(self.shared_x || self.shared_y) {
+            return canvases;
+        }
+        let union = |ranges: Vec<(f64, f64)>| {
+            ranges
+                .into_iter()
+                .filter(|r| r.0 < r.1)
+                .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)))
+        };
+        let x_range =
+            union(canvases.iter().map(Canvas::x_range).collect()).filter(|_| self.shared_x);
+        let y_range =
+            union(canvases.iter().map(Canvas::y_range).collect()).filter(|_| self.shared_y);
+        self.panels
+            .iter()
+            .zip(canvases)
+            .map(|((_, dataset, kind), canvas)| {
+                let view = (
+                    x_range.unwrap_or(canvas.x_range()),
+                    y_range.unwrap_or(canvas.y_range()),
+                );
+                self.draw_panel(dataset, *kind, Some(view))
+            })
+            .collect()
+    }
+
+    fn draw_panel(
+        &self,
+        dataset: &DataSet,
+        kind: PlotKind,
+        view: Option<((f64, f64), (f64, f64))>,
+    ) -> Canvas {
+        let (height, width) = self.panel_size();
+        let mut canvas = Canvas::from_size(height, width);
+        canvas.set_view(view);
+        // a panel without data stays empty
+        let _ = dataset.draw_into(&mut canvas, kind);
+        canvas
+    }
+}
+
+impl Display for Figure {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let (height, width) = self.panel_size();
+        let canvases = self.draw_panels();
+        let panels: Vec<(&String, Vec<String>)> = self
+            .panels
+            .iter()
+            .zip(&canvases)
+            .map(|((title, _, _), canvas)| {
+                (
+                    title,
+                    canvas.to_string().lines().map(String::from).collect(),
+                )
+            })
+            .collect();
+        for row in panels.chunks(self.layout.columns) {
+            let titles: Vec<String> = row
+                .iter()
+                .map(|(title, _)| {
+                    let title: String = title.chars().take(width).collect();
+                    format!("{title:^width$}")
+                })
+                .collect();
+            writeln!(f, "{}", titles.join(" ").trim_end())?;
+            for line in 0..height {
+                let cells: Vec<&str> = row.iter().map(|(_, lines)| lines[line].as_str()).collect();
+                writeln!(f, "{}", cells.join("|"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_parse() {
+        let layout: Layout = "2x3".parse().unwrap();
+        assert_eq!((layout.rows, layout.columns), (2, 3));
+        assert!("2x0".parse::<Layout>().is_err());
+        assert!("2".parse::<Layout>().is_err());
+        assert_eq!(Layout::fitting(3).to_string(), "2x2");
+        assert_eq!(Layout::fitting(1).to_string(), "1x1");
+    }
+
+    #[test]
+    fn figure_grid() {
+        let dataset = DataSet::from_csv("x,a,b\n0,0,10\n1,1,20\n2,4,30\n").unwrap();
+        let mut figure = Figure::facet_by_series(&dataset, PlotKind::Point, None);
+        let text = figure.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 25);
+        assert_eq!(lines[0].split_whitespace().collect::<Vec<_>>(), ["a", "b"]);
+        assert!(lines[1..].iter().all(|l| l.chars().count() == 79));
+        assert!(lines[1..].iter().all(|l| l.chars().nth(39) == Some('|')));
+
+        // b above a once ranges are shared
+        let highest = |text: &str, c| text.lines().skip(1).position(|l| l.contains(c)).unwrap();
+        assert_eq!(highest(&text, 'a'), highest(&text, 'b'));
+        figure.share_axes(false, true);
+        let text = figure.to_string();
+        assert!(highest(&text, 'b') < highest(&text, 'a'));
+
+        assert!(
+            figure
+                .add_panel("c", DataSet::default(), PlotKind::Point)
+                .is_err()
+        );
+
+        // rows are added to hold all datasets
+        let layout = Some(Layout {
+            rows: 1,
+            columns: 1,
+        });
+        let text = Figure::facet_by_series(&dataset, PlotKind::Point, layout).to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 24);
+        assert_eq!((lines[0].trim(), lines[12].trim()), ("a", "b"));
+    }
+}
@@ -1,11 +1,65 @@
 use clap::ValueEnum;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::BufRead;
 use std::iter::zip;
 use std::num::ParseFloatError;
 
+mod annotation;
+mod bar;
+mod color;
+mod density;
+mod downsample;
+mod error_bar;
+mod follow;
+mod grid;
+mod heatmap;
+mod interactive;
+mod layer;
+mod layout;
+mod overplot;
+mod qq;
+mod secondary;
+mod sketch;
+mod smooth;
+mod stats;
+mod stream;
+mod style;
+mod svg;
+mod time;
+mod trend;
+mod violin;
+mod watch;
+
+pub use annotation::{Abscissa, Annotation, Note, Span};
+pub use bar::{BarMode, Bars};
+pub use color::{Color, Shading};
+pub use density::{Bandwidth, Density, Kernel};
+pub use downsample::Downsampling;
+pub use error_bar::{ErrorBar, ErrorMapping, ErrorSpec};
+pub use follow::{Window, redraw, tail_lines};
+pub use heatmap::Heatmap;
+pub use interactive::{Key, Viewer};
+pub use layer::Layer;
+pub use layout::{Figure, Layout};
+pub use overplot::{COLLISION, Hits, Overplot};
+pub use qq::{QQ, QQReference};
+pub use sketch::{QuantileSketch, SKETCH_K};
+pub use smooth::{SmoothMapping, Smoothing};
+pub use stats::{PERCENTILES, Stats, StatsFormat, write_stats};
+use stream::parse_row;
+pub use stream::{Accumulator, CsvRows, StreamedDataSet};
+pub use style::{LineStyle, Style, StyleMapping};
+use time::time_ticks;
+pub use time::{TimeUnit, format_time, parse_time};
+pub use trend::{Fit, Trend};
+pub use violin::Violin;
+pub use watch::FileWatcher;
+
 const MARGIN: f64 = 0.0;
 
 #[derive(Debug)]
@@ -14,6 +68,8 @@ pub enum CanvasError {
     OutOfRange(String),
     /// No data to plot
     NoData,
+    /// Plot not available for the data
+    Unsupported(String),
 }
 
 impl Display for CanvasError {
@@ -21,6 +77,7 @@ impl Display for CanvasError {
         match self {
             Self::OutOfRange(s) => write!(f, "Canvas Error: Out of range {}", s),
             Self::NoData => write!(f, "Canvas Error: No Data"),
+            Self::Unsupported(s) => write!(f, "Canvas Error: Unsupported {}", s),
         }
     }
 }
@@ -52,15 +109,37 @@ impl From<ParseFloatError> for DatasetError {
     }
 }
 
+impl From<io::Error> for DatasetError {
+    fn from(err: io::Error) -> DatasetError {
+        Self::InvalidData(err.to_string())
+    }
+}
+
 /// Where to plot
 #[derive(Default, Debug)]
 pub struct Canvas {
-    /// Vec<line: Vec<u8>>
-    cells: Vec<Vec<u8>>,
+    /// Vec<line: Vec<char>>
+    cells: Vec<Vec<char>>,
+    /// Background color of each cell
+    backgrounds: Vec<Vec<Option<Color>>>,
+    /// Color of the character of each cell
+    foregrounds: Vec<Vec<Option<Color>>>,
     width: usize,
     height: usize,
     x_range: (f64, f64),
     y_range: (f64, f64),
+    /// Whether the y axis uses a logarithmic scale
+    log_y: bool,
+    /// (x_range, y_range) imposed on the drawings, e.g. when zooming
+    view: Option<((f64, f64), (f64, f64))>,
+    /// Range of the secondary y axis, if any
+    y2_range: Option<(f64, f64)>,
+    /// (line, column) where the axes cross, ticks being every 5 cells from it
+    origin: Option<(usize, usize)>,
+    /// Color of the characters set, see [Canvas::set_pen]
+    pen: Option<Color>,
+    /// Areas shaded in SVG output, see [Canvas::add_band]
+    bands: Vec<svg::Band>,
 }
 
 impl Canvas {
@@ -70,21 +149,53 @@ impl Canvas {
 
     fn from_size(height: usize, width: usize) -> Self {
         Self {
-            cells: (0..height).map(|_| vec![b' '; width]).collect(),
+            cells: (0..height).map(|_| vec![' '; width]).collect(),
+            backgrounds: vec![vec![None; width]; height],
+            foregrounds: vec![vec![None; width]; height],
             width,
             height,
             x_range: (0.0, 0.0),
             y_range: (0.0, 0.0),
+            log_y: false,
+            view: None,
+            y2_range: None,
+            origin: None,
+            pen: None,
+            bands: Vec::new(),
         }
     }
 
     /// Remove drawing
     pub fn clear(&mut self) {
-        self.cells = (0..self.height).map(|_| vec![b' '; self.width]).collect()
+        self.cells = (0..self.height).map(|_| vec![' '; self.width]).collect();
+        self.backgrounds = vec![vec![None; self.width]; self.height];
+        self.foregrounds = vec![vec![None; self.width]; self.height];
+        self.log_y = false;
+        self.y2_range = None;
+        self.origin = None;
+        self.pen = None;
+        self.bands.clear();
+    }
+
+    /// Impose ranges on the next drawings, instead of the ones fitting the data
+    pub fn set_view(&mut self, view: Option<((f64, f64), (f64, f64))>) {
+        self.view = view;
+    }
+
+    pub fn x_range(&self) -> (f64, f64) {
+        self.x_range
+    }
+
+    pub fn y_range(&self) -> (f64, f64) {
+        self.y_range
     }
 
     fn set_x_range(&mut self, x_min: f64, x_max: f64) {
         assert!(x_min < x_max);
+        if let Some((x_range, _)) = self.view {
+            self.x_range = x_range;
+            return;
+        }
         let delta = x_max - x_min;
         let x_range = (x_min - MARGIN * delta, x_max + MARGIN * delta);
         let cell_width = (x_range.1 - x_range.0) / self.width as f64;
@@ -96,6 +207,10 @@ impl Canvas {
 
     fn set_y_range(&mut self, y_min: f64, y_max: f64) {
         assert!(y_min < y_max);
+        if let Some((_, y_range)) = self.view {
+            self.y_range = y_range;
+            return;
+        }
         let delta = y_max - y_min;
         let y_range = (y_min - MARGIN * delta, y_max + MARGIN * delta);
         let cell_width = (y_range.1 - y_range.0) / self.height as f64;
@@ -105,8 +220,23 @@ impl Canvas {
         );
     }
 
+    /// Set the x range without margins, unless a view is imposed
+    fn set_x_bounds(&mut self, x_min: f64, x_max: f64) {
+        self.x_range = self.view.map_or((x_min, x_max), |v| v.0);
+    }
+
+    /// Set the y range without margins, unless a view is imposed
+    ///
+    /// Views reaching non positive values are ignored on logarithmic axes.
+    fn set_y_bounds(&mut self, y_min: f64, y_max: f64) {
+        self.y_range = match self.view {
+            Some((_, view)) if !self.log_y || view.0 > 0.0 => view,
+            _ => (y_min, y_max),
+        };
+    }
+
     /// Put a specific value in a specific cell
-    fn set_cell(&mut self, line: usize, column: usize, value: u8) -> Result<(), CanvasError> {
+    fn set_cell(&mut self, line: usize, column: usize, value: char) -> Result<(), CanvasError> {
         if let Some(cell) = self
             .cells
             .get_mut(line)
@@ -114,6 +244,7 @@ impl Canvas {
             .get_mut(column)
         {
             *cell = value;
+            self.foregrounds[line][column] = self.pen;
             Ok(())
         } else {
             Err(CanvasError::OutOfRange(format!(
@@ -123,27 +254,166 @@ impl Canvas {
         }
     }
 
-    fn get_mut_cell(&mut self, line: usize, column: usize) -> Option<&mut u8> {
-        todo!()
+    /// Set the background color of a specific cell
+    fn set_background(
+        &mut self,
+        line: usize,
+        column: usize,
+        color: Color,
+    ) -> Result<(), CanvasError> {
+        match self
+            .backgrounds
+            .get_mut(line)
+            .and_then(|l| l.get_mut(column))
+        {
+            Some(cell) => {
+                *cell = Some(color);
+                Ok(())
+            }
+            None => Err(CanvasError::OutOfRange(format!(
+                "try to color ({}, {}) (Canvas size: ({}, {}))",
+                line, column, &self.height, &self.width
+            ))),
+        }
+    }
+
+    /// Write a text, starting at a specific cell
+    fn draw_text(&mut self, line: usize, column: usize, text: &str) -> Result<(), CanvasError> {
+        for (idx, c) in text.chars().enumerate() {
+            self.set_cell(line, column + idx, c)?;
+        }
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    fn get_mut_cell(&mut self, line: usize, column: usize) -> Option<&mut char> {
+        self.cells.get_mut(line)?.get_mut(column)
+    }
+
+    /// Get the line corresponding to a specific y value
+    fn get_line(&self, y: f64) -> Result<usize, CanvasError> {
+        let offset = if self.log_y {
+            get_cell(
+                y.log10(),
+                self.y_range.0.log10(),
+                self.y_range.1.log10(),
+                self.height,
+            )?
+        } else {
+            get_cell(y, self.y_range.0, self.y_range.1, self.height)?
+        };
+        Ok(self.height - offset)
+    }
+
+    /// Get the line corresponding to a specific y value, clamped to the canvas
+    fn get_clamped_line(&self, y: f64) -> usize {
+        let (y_min, y_max) = self.y_range;
+        let y = if self.log_y && y <= 0.0 {
+            y_min
+        } else {
+            y.clamp(y_min, y_max)
+        };
+        self.get_line(y)
+            .expect("value should be in range")
+            .min(self.height - 1)
+    }
+
+    /// Get the column corresponding to a specific x value, clamped to the canvas
+    fn get_clamped_column(&self, x: f64) -> usize {
+        let (x_min, x_max) = self.x_range;
+        get_cell(x.clamp(x_min, x_max), x_min, x_max, self.width).expect("value should be in range")
+    }
+
+    /// Draw a vertical line between two y values, clipped to the canvas
+    fn draw_vline(&mut self, x: f64, y0: f64, y1: f64, value: char) -> Result<(), CanvasError> {
+        let Ok(column) = get_cell(x, self.x_range.0, self.x_range.1, self.width) else {
+            return Ok(());
+        };
+        let (l0, l1) = (self.get_clamped_line(y0), self.get_clamped_line(y1));
+        for line in l0.min(l1)..=l0.max(l1) {
+            self.set_cell(line, column, value)?;
+        }
+        Ok(())
+    }
+
+    /// Draw a horizontal line between two x values, clipped to the canvas
+    fn draw_hline(&mut self, y: f64, x0: f64, x1: f64, value: char) -> Result<(), CanvasError> {
+        let Some(line) = self.get_line(y).ok().filter(|&l| l < self.height) else {
+            return Ok(());
+        };
+        let (c0, c1) = (self.get_clamped_column(x0), self.get_clamped_column(x1));
+        for column in c0.min(c1)..=c0.max(c1) {
+            self.set_cell(line, column, value)?;
+        }
+        Ok(())
+    }
+
+    /// Draw a function evaluated on each column,
+    /// consecutive columns being linked by vertical lines
+    fn draw_curve(&mut self, f: impl Fn(f64) -> f64, value: char) -> Result<(), CanvasError> {
+        self.draw_pattern(f, value, |_| true)
+    }
+
+    /// Draw a function on the columns selected by the pattern,
+    /// consecutive selected columns being linked by vertical lines
+    fn draw_pattern(
+        &mut self,
+        f: impl Fn(f64) -> f64,
+        value: char,
+        pattern: impl Fn(usize) -> bool,
+    ) -> Result<(), CanvasError> {
+        let delta = (self.x_range.1 - self.x_range.0) / self.width as f64;
+        let mut previous = None;
+        for c in 0..self.width {
+            let y = f(self.x_range.0 + delta * c as f64);
+            if y.is_nan() || !pattern(c) {
+                previous = None;
+                continue;
+            }
+            let line = self.get_clamped_line(y);
+            let (start, end) = match previous {
+                Some(p) if p < line => (p + 1, line),
+                Some(p) if p > line => (line, p - 1),
+                _ => (line, line),
+            };
+            for l in start..=end {
+                self.set_cell(l, c, value)?;
+            }
+            previous = Some(line);
+        }
+        Ok(())
     }
 
     /// Put a specific value with specific coordinates in the canvas
-    fn draw_value(&mut self, x: f64, y: f64, value: u8) -> Result<(), CanvasError> {
+    fn draw_value(&mut self, x: f64, y: f64, value: char) -> Result<(), CanvasError> {
         self.set_cell(
-            self.height - get_cell(y, self.y_range.0, self.y_range.1, self.height)?,
+            self.get_line(y)?,
             get_cell(x, self.x_range.0, self.x_range.1, self.width)?,
             value,
         )
     }
 
-    fn get_mut_value(&mut self, x: f64, y: f64) -> Option<&mut u8> {
-        let offset = get_cell(y, self.y_range.0, self.y_range.1, self.height).ok()?;
-        let line = self.height - offset;
+    #[allow(dead_code)]
+    fn get_mut_value(&mut self, x: f64, y: f64) -> Option<&mut char> {
+        let line = self.get_line(y).ok()?;
         let column = get_cell(x, self.x_range.0, self.x_range.1, self.width).ok()?;
         self.get_mut_cell(line, column)
     }
 
-    /// Draw axes
+    /// Put a value in a cell, unless something is already drawn there
+    fn set_empty_cell(
+        &mut self,
+        line: usize,
+        column: usize,
+        value: char,
+    ) -> Result<(), CanvasError> {
+        match self.get_mut_cell(line, column) {
+            Some(' ') | None => self.set_cell(line, column, value),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Draw axes, under previous drawings
     fn draw_axes(&mut self) -> Result<(), CanvasError> {
         let y_axis_location = match get_cell(0.0, self.x_range.0, self.x_range.1, self.width) {
             Ok(u) => u,
@@ -155,8 +425,8 @@ impl Canvas {
                 }
             }
         };
-        let x_axis_location = match get_cell(0.0, self.y_range.0, self.y_range.1, self.height) {
-            Ok(u) => u,
+        let x_axis_location = match self.get_line(0.0) {
+            Ok(u) => u.min(self.height - 1),
             _ => {
                 if self.y_range.1 < 0.0 {
                     0
@@ -165,21 +435,35 @@ impl Canvas {
                 }
             }
         };
+        // the origin is a corner when the axes meet on the border
+        let origin = match (
+            x_axis_location == 0,
+            x_axis_location == self.height - 1,
+            y_axis_location == 0,
+            y_axis_location == self.width - 1,
+        ) {
+            (true, _, true, _) => '┌',
+            (true, _, _, true) => '┐',
+            (_, true, true, _) => '└',
+            (_, true, _, true) => '┘',
+            _ => '┼',
+        };
+        self.set_empty_cell(x_axis_location, y_axis_location, origin)?;
+        self.origin = Some((x_axis_location, y_axis_location));
         for cell in 0..self.width {
             let c = match (cell as i32 - y_axis_location as i32) % 5 {
-                0 => b'+',
-                _ => b'-',
+                0 => '┼',
+                _ => '─',
             };
-            self.set_cell(x_axis_location, cell, c)?;
+            self.set_empty_cell(x_axis_location, cell, c)?;
         }
         for cell in 0..self.height {
             let c = match (cell as i32 - x_axis_location as i32) % 5 {
-                0 => b'+',
-                _ => b'|',
+                0 => '┼',
+                _ => '│',
             };
-            self.set_cell(cell, y_axis_location, c)?;
+            self.set_empty_cell(cell, y_axis_location, c)?;
         }
-        self.set_cell(x_axis_location, y_axis_location, b'+')?;
         Ok(())
     }
 }
@@ -196,18 +480,27 @@ fn get_cell(x: f64, x_min: f64, x_max: f64, width: usize) -> Result<usize, Canva
 
 impl Display for Canvas {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-        for line in &self.cells {
-            writeln!(
-                f,
-                "{}",
-                line.iter().map(|&c| { c as char }).collect::<String>()
-            )?;
+        for ((line, backgrounds), foregrounds) in
+            zip(zip(&self.cells, &self.backgrounds), &self.foregrounds)
+        {
+            for ((&c, background), foreground) in zip(zip(line, backgrounds), foregrounds) {
+                let escape: String = [(background, true), (foreground, false)]
+                    .iter()
+                    .filter_map(|(color, background)| color.map(|c| c.escape(*background)))
+                    .collect();
+                if escape.is_empty() {
+                    write!(f, "{c}")?;
+                } else {
+                    write!(f, "{escape}{c}\x1b[0m")?;
+                }
+            }
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
-#[derive(Debug, Default, Copy, Clone, ValueEnum)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, ValueEnum)]
 pub enum PlotKind {
     /// Points
     #[default]
@@ -219,8 +512,26 @@ pub enum PlotKind {
     /// Cumulative distribution function
     CDF,
 
+    /// Complementary cumulative distribution function (1 - CDF), on a log scale
+    CCDF,
+
     /// Histogram
     Histogram,
+
+    /// Kernel density estimate
+    Density,
+
+    /// Mirrored density estimate, highliting quartiles
+    Violin,
+
+    /// Quantiles against the quantiles of a reference distribution
+    QQ,
+
+    /// Number of points in each cell
+    Heatmap,
+
+    /// Bars, one group per distinct x value or category
+    Bar,
 }
 
 impl Display for PlotKind {
@@ -229,7 +540,13 @@ impl Display for PlotKind {
             PlotKind::Point => write!(f, "point"),
             PlotKind::Boxplot => write!(f, "boxplot"),
             PlotKind::CDF => write!(f, "cdf"),
+            PlotKind::CCDF => write!(f, "ccdf"),
             PlotKind::Histogram => write!(f, "histogram"),
+            PlotKind::Density => write!(f, "density"),
+            PlotKind::Violin => write!(f, "violin"),
+            PlotKind::QQ => write!(f, "qq"),
+            PlotKind::Heatmap => write!(f, "heatmap"),
+            PlotKind::Bar => write!(f, "bar"),
         }
     }
 }
@@ -253,26 +570,26 @@ impl Quantiles {
             "not enough valid values in input ({input:?})"
         );
         x.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let [q1, q2, q3] =
-            [0.25, 0.5, 0.75].map(|q| get_value(x.as_slice(), get_index(q, x.len())).unwrap());
+        let [q1, q2, q3] = [0.25, 0.5, 0.75].map(|q| get_quantile(x.as_slice(), q));
         let lower = q2 - inter_quartiles * (q3 - q1);
         let upper = q2 + inter_quartiles * (q3 - q1);
+        // the median is within the bounds, so at least one value is
         Self {
             min: x
                 .iter()
-                .filter(|&a| *a > lower)
+                .filter(|&a| *a >= lower)
                 .copied()
                 .min_by(|a, b| a.partial_cmp(b).unwrap())
-                .unwrap(),
+                .expect("the maximum is not below the median"),
             q1,
             q2,
             q3,
             max: x
                 .iter()
-                .filter(|&a| *a < upper)
+                .filter(|&a| *a <= upper)
                 .copied()
                 .max_by(|a, b| a.partial_cmp(b).unwrap())
-                .unwrap(),
+                .expect("the minimum is not above the median"),
             outliers: x
                 .iter()
                 .filter(|&a| *a < lower || *a > upper)
@@ -281,6 +598,36 @@ impl Quantiles {
         }
     }
 
+    /// Lowest value that is not an outlier
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// First quartile
+    pub fn q1(&self) -> f64 {
+        self.q1
+    }
+
+    /// Median
+    pub fn median(&self) -> f64 {
+        self.q2
+    }
+
+    /// Third quartile
+    pub fn q3(&self) -> f64 {
+        self.q3
+    }
+
+    /// Highest value that is not an outlier
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Values outside of the whiskers
+    pub fn outliers(&self) -> &[f64] {
+        &self.outliers
+    }
+
     /// Draw a horizontal boxplot on the canvas from lines height to height+3
     pub fn draw_into(&self, canvas: &mut Canvas, height: usize) -> Result<(), CanvasError> {
         assert!(canvas.height >= height + 3);
@@ -295,20 +642,34 @@ impl Quantiles {
         let (q1, q2, q3) = (q1?, q2?, q3?);
         let (min, max) = (min?, max?);
         for x in (min + 1)..q1 {
-            canvas.set_cell(height + 1, x, b'-')?;
+            canvas.set_cell(height + 1, x, '-')?;
         }
         for x in (q3 + 1)..max {
-            canvas.set_cell(height + 1, x, b'-')?;
+            canvas.set_cell(height + 1, x, '-')?;
         }
         for x in outliers {
-            canvas.set_cell(height + 1, x?, b'+')?;
+            canvas.set_cell(height + 1, x?, '+')?;
         }
         for x in q1..q3 {
-            canvas.set_cell(height, x, b'-')?;
-            canvas.set_cell(height + 2, x, b'-')?;
+            canvas.set_cell(height, x, '-')?;
+            canvas.set_cell(height + 2, x, '-')?;
         }
         for x in [min, q1, q2, q3, max] {
-            canvas.set_cell(height + 1, x, b'|')?;
+            canvas.set_cell(height + 1, x, '|')?;
+        }
+        Ok(())
+    }
+
+    /// Draw the quartiles on a single line, linked by a dash
+    pub fn draw_quartiles_into(&self, canvas: &mut Canvas, line: usize) -> Result<(), CanvasError> {
+        let [q1, q2, q3] = [self.q1, self.q2, self.q3]
+            .map(|x| get_cell(x, canvas.x_range.0, canvas.x_range.1, canvas.width));
+        let (q1, q2, q3) = (q1?, q2?, q3?);
+        for x in q1..q3 {
+            canvas.set_cell(line, x, '-')?;
+        }
+        for x in [q1, q2, q3] {
+            canvas.set_cell(line, x, '|')?;
         }
         Ok(())
     }
@@ -316,46 +677,64 @@ impl Quantiles {
 
 #[derive(Debug, Default)]
 pub struct CDF {
+    /// Points where the distribution changes, sorted by value
     steps: Vec<(f64, f64)>,
+    /// Whether this is the complementary distribution (1 - CDF)
+    complementary: bool,
 }
 
 impl CDF {
     pub fn from_vec(input: Vec<f64>) -> Self {
-        let step = 1.0 / (input.len() as f64);
-        let mut steps: Vec<(f64, f64)> = Vec::new();
-        let mut input: Vec<f64> = input.iter().filter(|y| !y.is_nan()).copied().collect();
+        let mut input: Vec<f64> = input.into_iter().filter(|y| !y.is_nan()).collect();
         input.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let mut cur = 0.0;
-        for y in input {
-            cur += step;
-            if let Some(point) = steps.iter_mut().find(|elt| elt.0 == y) {
-                point.1 = cur;
-            } else {
-                steps.push((y, cur));
+        let nb = input.len() as f64;
+        let mut steps: Vec<(f64, f64)> = Vec::new();
+        for (idx, y) in input.into_iter().enumerate() {
+            let cur = (idx + 1) as f64 / nb;
+            match steps.last_mut() {
+                Some(point) if point.0 == y => point.1 = cur,
+                _ => steps.push((y, cur)),
             }
         }
-        Self { steps }
+        Self {
+            steps,
+            complementary: false,
+        }
     }
 
-    pub fn draw_into(&self, canvas: &mut Canvas, symbole: u8) -> Result<(), CanvasError> {
-        let delta = (canvas.x_range.1 - canvas.x_range.0) / canvas.width as f64;
-        for c in 0..=canvas.width {
-            let x = canvas.x_range.0 + delta * c as f64;
-            let y = self.get_value(x);
-            canvas.draw_value(x, y, symbole)?;
+    /// Turn into the complementary cumulative distribution function (survival function)
+    pub fn complementary(self) -> Self {
+        Self {
+            complementary: true,
+            ..self
         }
-        Ok(())
+    }
+
+    /// Points where the distribution changes
+    pub fn steps(&self) -> Vec<(f64, f64)> {
+        self.steps
+            .iter()
+            .map(|&(x, y)| (x, self.orient(y)))
+            .collect()
+    }
+
+    /// Draw the distribution as steps, jumps being drawn as vertical lines
+    pub fn draw_into(&self, canvas: &mut Canvas, symbole: char) -> Result<(), CanvasError> {
+        canvas.draw_curve(|x| self.get_value(x), symbole)
     }
 
     /// Get the value of the CDF evaluted on x
     fn get_value(&self, x: f64) -> f64 {
-        let mut y = 0.0;
-        for p in &self.steps {
-            if p.0 < x {
-                y = p.1;
-            }
-        }
-        y
+        let idx = self.steps.partition_point(|p| p.0 <= x);
+        let y = match idx {
+            0 => 0.0,
+            i => self.steps[i - 1].1,
+        };
+        self.orient(y)
+    }
+
+    fn orient(&self, y: f64) -> f64 {
+        if self.complementary { 1.0 - y } else { y }
     }
 }
 
@@ -365,6 +744,8 @@ pub struct Histogram {
     bins: Vec<f64>,
     /// number of sample per bins
     values: Vec<usize>,
+    /// Whether bars are scaled so that their area is 1, as a density
+    density: bool,
 }
 
 impl Histogram {
@@ -378,40 +759,53 @@ impl Histogram {
             .iter()
             .copied()
             .fold((first, first), |(mi, ma), x| (x.min(mi), x.max(ma)));
-        let x_max = x_max + 0.001 * (x_max - x_min);
+        let mut hist = Histogram::from_range(x_min, x_max, bin_nb);
+        hist.add_values(&input);
+        hist
+    }
+
+    /// Empty histogram whose bins cover the extrema, a single value being
+    /// centered in a range of width 1
+    pub(crate) fn from_range(x_min: f64, x_max: f64, bin_nb: usize) -> Self {
+        let (x_min, x_max) = if x_min < x_max {
+            (x_min, x_max + 0.001 * (x_max - x_min))
+        } else {
+            (x_min - 0.5, x_max + 0.5)
+        };
         let mut hist = Histogram::default();
         hist.reset_bins(x_min, x_max, bin_nb);
-        hist.add_values(&input);
         hist
     }
 
+    /// Scale the bars so that their area is 1, to be compared with a density
+    pub fn into_density(self) -> Self {
+        Self {
+            density: true,
+            ..self
+        }
+    }
+
     /// get bin number into which the value should go.
     fn get_bin(&self, x: f64) -> Option<usize> {
         if x.is_nan() {
             return None;
         }
-        if let Some(first) = self.bins.first() {
-            if &x < first || &x > self.bins.last().expect("at least one item") {
-                None
-            } else {
-                for (idx, b) in self.bins.iter().skip(1).enumerate() {
-                    if x < *b {
-                        return Some(idx);
-                    }
-                }
-                unreachable!()
-            }
-        } else {
-            None
+        let (first, last) = (self.bins.first()?, self.bins.last()?);
+        if x < *first || x > *last {
+            return None;
         }
+        // the upper boundary belongs to the last bin
+        let idx = self.bins.partition_point(|b| *b <= x);
+        Some((idx - 1).min(self.values.len() - 1))
     }
 
-    pub fn draw_into(&self, canvas: &mut Canvas, label: u8) -> Result<(), CanvasError> {
+    pub fn draw_into(&self, canvas: &mut Canvas, label: char) -> Result<(), CanvasError> {
         let step = (canvas.x_range.1 - canvas.x_range.0) / (canvas.width as f64);
         let start = canvas.x_range.0;
         let xs = (0..canvas.width).map(|a| start + a as f64 * step);
         for x in xs {
-            canvas.draw_value(x, self.get_value(x).unwrap(), label)?
+            // values out of the canvas, e.g. when zooming, are skipped
+            let _ = canvas.draw_value(x, self.get_value(x).unwrap(), label);
         }
         Ok(())
     }
@@ -423,13 +817,29 @@ impl Histogram {
             return None;
         }
         if let Some(b) = self.get_bin(x) {
-            self.values.get(b).map(|&x| x as f64)
+            self.values.get(b).map(|&x| self.scale(x))
         } else {
             Some(0.0)
         }
     }
 
+    /// Height of a bar holding a number of values
+    fn scale(&self, count: usize) -> f64 {
+        if !self.density {
+            return count as f64;
+        }
+        let nb = self.values.iter().sum::<usize>() as f64;
+        let bin_size = self.bins[1] - self.bins[0];
+        count as f64 / (nb * bin_size)
+    }
+
+    /// Height of the highest bar
+    fn max_value(&self) -> f64 {
+        self.scale(self.values.iter().copied().max().unwrap_or(0))
+    }
+
     /// Get the normalized value of the histogram at specific value
+    #[allow(dead_code)]
     fn get_frequency(&self, x: f64) -> Option<f64> {
         let nb = self.values.iter().sum::<usize>() as f64;
         self.get_value(x).map(|x| x / nb)
@@ -453,10 +863,59 @@ impl Histogram {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct DataSet {
     /// label: list of points
     dataset: HashMap<String, Vec<(f64, f64)>>,
+    /// Labels of the CSV columns, the first one excluded, to append rows
+    columns: Vec<String>,
+    /// Unit of x values, if they are unix timestamps
+    time_unit: Option<TimeUnit>,
+    /// (label, columns, along x) of the uncertainties, to append rows
+    error_specs: Vec<(String, ErrorSpec, bool)>,
+    /// Names of the categories, if x is categorical
+    /// The x value of a point is the index of its category
+    categories: Vec<String>,
+    /// Kernel used for density estimates
+    kernel: Kernel,
+    /// Bandwidth used for density estimates
+    bandwidth: Bandwidth,
+    /// Distribution the quantiles are compared to
+    qq_reference: QQReference,
+    /// How bars of different datasets are laid out
+    bar_mode: BarMode,
+    /// Whether bars grow rightward
+    horizontal: bool,
+    /// Whether x values are seconds since 1970-01-01T00:00:00Z
+    time_axis: bool,
+    /// Format of time labels, overriding the one chosen from the time span
+    time_format: Option<String>,
+    /// How densities are rendered
+    shading: Shading,
+    /// How cells hit by several points are rendered
+    overplot: Overplot,
+    /// label: uncertainty of each point
+    errors: HashMap<String, Vec<ErrorBar>>,
+    /// Model fitted and drawn over each dataset, if any
+    trend: Option<Fit>,
+    /// label (None for all datasets): transform applied before drawing points
+    smoothing: HashMap<Option<String>, Smoothing>,
+    /// Whether raw points are drawn along smoothed datasets
+    show_raw: bool,
+    /// How large datasets are reduced before drawing points
+    downsampling: Downsampling,
+    /// Size above which quantiles are estimated with a sketch
+    sketch_threshold: Option<usize>,
+    /// label: marker, color, line and visibility of the dataset
+    styles: HashMap<String, Style>,
+    /// Labels of the datasets drawn against the secondary y axis
+    secondary: Vec<String>,
+    /// Reference lines, text and regions drawn over the plot
+    annotations: Vec<Annotation>,
+    /// Whether grid lines are drawn at the ticks of the axes
+    grid: bool,
+    /// Whether histograms are scaled as densities
+    histogram_density: bool,
 }
 
 impl DataSet {
@@ -468,36 +927,153 @@ impl DataSet {
     ///  -1  , 0 , 1 , 3
     ///  -5  , 1 , -2, 4
     /// ```
+    ///
+    /// The kind of the values of the first column is chosen on the first row:
+    /// numbers, dates (see [parse_time]) or categories. Rows of another kind
+    /// are an error.
     pub fn from_csv(content: &str) -> Result<Self, DatasetError> {
-        let sep = ',';
+        Self::from_reader(content.as_bytes())
+    }
+
+    /// Read a CSV document line by line (see [DataSet::from_csv])
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, DatasetError> {
         let mut dataset = Self::default();
-        let mut lines = content.lines();
-        let headers: Vec<_> = lines
-            .next()
-            .ok_or(DatasetError::NoData)?
-            .split(sep)
-            .map(|l| String::from(l.replace('"', "").trim()))
-            .skip(1)
-            .collect();
-        for line in lines {
-            let mut values = line
-                .split(sep)
-                .map(|l| String::from(l.replace('"', "").trim()));
-            let x = values
-                .next()
-                .expect("first column (indexes) should exist")
-                .parse()?;
-            for (label, y) in zip(headers.clone(), values) {
+        let rows = CsvRows::new(reader)?;
+        let headers = rows.headers().to_vec();
+        dataset.columns = headers.clone();
+        let invalid = |idx: usize, x: &str, kind: &str| {
+            DatasetError::InvalidData(format!(
+                "row {}: x value {x} is {kind}, unlike the first row",
+                idx + 1
+            ))
+        };
+        // the kind of x values is chosen on the first row
+        let mut categorical = false;
+        for (idx, row) in rows.enumerate() {
+            let (x, values) = row?;
+            if idx == 0 {
+                categorical = x.parse::<f64>().is_err();
+                dataset.time_axis = categorical && parse_time(&x).is_some();
+            }
+            let x = match (x.parse::<f64>(), categorical) {
+                (Ok(x), false) => x,
+                (Err(_), true) if dataset.time_axis => {
+                    parse_time(&x).ok_or_else(|| invalid(idx, &x, "not a date"))?
+                }
+                (Err(_), true) => dataset.get_category(x),
+                (Ok(_), true) => return Err(invalid(idx, &x, "a number")),
+                (Err(_), false) => return Err(invalid(idx, &x, "not a number")),
+            };
+            for (label, y) in zip(&headers, values) {
                 dataset
                     .dataset
-                    .entry(label)
+                    .entry(label.clone())
                     .or_default()
-                    .push((x, y.parse()?));
+                    .push((x, y));
             }
         }
         Ok(dataset)
     }
 
+    /// Append a row of the CSV document the dataset has been read from
+    ///
+    /// Blank lines are ignored. The uncertainties of the appended points are
+    /// computed from the error columns set beforehand.
+    pub fn append_csv_line(&mut self, line: &str) -> Result<(), DatasetError> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        let (x, values) = parse_row(line)?;
+        let x = self.parse_x(x)?;
+        let row: HashMap<&String, f64> = zip(&self.columns, values).collect();
+        for (label, &y) in &row {
+            self.dataset
+                .entry(label.to_string())
+                .or_default()
+                .push((x, y));
+        }
+        for (label, spec, along_x) in &self.error_specs {
+            let (Some(&y), Some(values)) = (
+                row.get(label),
+                spec.columns()
+                    .into_iter()
+                    .map(|c| row.get(c).copied())
+                    .collect::<Option<Vec<f64>>>(),
+            ) else {
+                continue;
+            };
+            let interval = spec.interval(if *along_x { x } else { y }, &values);
+            let len = self.dataset[label].len();
+            let errors = self.errors.entry(label.clone()).or_default();
+            errors.resize(len, ErrorBar::default());
+            let error = &mut errors[len - 1];
+            if *along_x {
+                error.x = Some(interval);
+            } else {
+                error.y = Some(interval);
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the x value of a row, the kind of x values being chosen on the
+    /// first row if it has not been yet
+    fn parse_x(&mut self, x: String) -> Result<f64, DatasetError> {
+        if let Some(unit) = self.time_unit {
+            return Ok(x.parse::<f64>()? / unit.per_second());
+        }
+        if self.time_axis {
+            return parse_time(&x)
+                .ok_or_else(|| DatasetError::InvalidData(format!("{x} is not a date")));
+        }
+        if !self.categories.is_empty() {
+            return Ok(self.get_category(x));
+        }
+        match x.parse::<f64>() {
+            Ok(x) => Ok(x),
+            Err(_) if self.dataset.values().all(|points| points.is_empty()) => {
+                match parse_time(&x) {
+                    Some(t) => {
+                        self.time_axis = true;
+                        Ok(t)
+                    }
+                    None => Ok(self.get_category(x)),
+                }
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Whether a column holds the uncertainty of another dataset, and is
+    /// hidden rather than plotted
+    fn is_error_column(&self, column: &str) -> bool {
+        self.error_specs
+            .iter()
+            .any(|(label, spec, _)| label != column && spec.columns().iter().any(|c| *c == column))
+    }
+
+    /// Drop the points out of a window
+    pub fn apply_window(&mut self, window: Window) {
+        let x_max = self
+            .dataset
+            .values()
+            .flatten()
+            .map(|p| p.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        for (label, points) in self.dataset.iter_mut() {
+            let keep: Vec<bool> = match window {
+                Window::Points(n) => (0..points.len()).map(|i| i + n >= points.len()).collect(),
+                Window::Span(span) => points.iter().map(|p| p.0 >= x_max - span).collect(),
+            };
+            let mut kept = keep.iter();
+            points.retain(|_| *kept.next().expect("one flag per point"));
+            if let Some(errors) = self.errors.get_mut(label) {
+                let mut kept = keep.iter();
+                errors.retain(|_| kept.next().copied().unwrap_or(true));
+            }
+        }
+    }
+
     pub fn add_points(&mut self, dataset: String, points: Vec<(f64, f64)>) {
         self.dataset
             .entry(dataset)
@@ -505,66 +1081,412 @@ impl DataSet {
             .extend(points.iter())
     }
 
+    /// Add points whose x is a category
+    pub fn add_categorical_points(&mut self, dataset: String, points: Vec<(String, f64)>) {
+        let points = points
+            .into_iter()
+            .map(|(category, y)| (self.get_category(category), y))
+            .collect();
+        self.add_points(dataset, points)
+    }
+
+    /// Use columns as the uncertainty of the y values of a dataset
+    ///
+    /// The columns are not plotted, but can hold the uncertainty of other datasets
+    pub fn set_y_error(&mut self, label: &str, spec: ErrorSpec) -> Result<(), DatasetError> {
+        self.set_error(label, spec, false)
+    }
+
+    /// Use columns as the uncertainty of the x values of a dataset
+    ///
+    /// The columns are not plotted, but can hold the uncertainty of other datasets
+    pub fn set_x_error(&mut self, label: &str, spec: ErrorSpec) -> Result<(), DatasetError> {
+        self.set_error(label, spec, true)
+    }
+
+    fn set_error(
+        &mut self,
+        label: &str,
+        spec: ErrorSpec,
+        along_x: bool,
+    ) -> Result<(), DatasetError> {
+        // columns without rows yet are known from the header
+        let no_points = Vec::new();
+        let get = |c: &str| {
+            self.dataset
+                .get(c)
+                .or_else(|| self.columns.iter().any(|l| l == c).then_some(&no_points))
+        };
+        let points = get(label)
+            .ok_or_else(|| DatasetError::InvalidData(format!("unknown dataset {label}")))?;
+        let columns = spec
+            .columns()
+            .into_iter()
+            .map(|c| match get(c) {
+                Some(column) if column.len() == points.len() => Ok(column),
+                Some(_) => Err(DatasetError::InvalidData(format!(
+                    "{c} and {label} have different lengths"
+                ))),
+                None => Err(DatasetError::InvalidData(format!("unknown dataset {c}"))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let intervals: Vec<(f64, f64)> = points
+            .iter()
+            .enumerate()
+            .map(|(idx, p)| {
+                let values: Vec<f64> = columns.iter().map(|c| c[idx].1).collect();
+                spec.interval(if along_x { p.0 } else { p.1 }, &values)
+            })
+            .collect();
+        let errors = self.errors.entry(label.into()).or_default();
+        errors.resize(intervals.len().max(errors.len()), ErrorBar::default());
+        for (error, interval) in zip(errors.iter_mut(), intervals) {
+            if along_x {
+                error.x = Some(interval);
+            } else {
+                error.y = Some(interval);
+            }
+        }
+        // a new uncertainty replaces the previous one
+        self.error_specs
+            .retain(|(l, _, x)| l != label || *x != along_x);
+        self.error_specs.push((label.into(), spec, along_x));
+        Ok(())
+    }
+
+    /// Names of the categories, indexed by x value
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    /// Symbol of a dataset, the first letter of its label by default
+    fn marker(&self, label: &str) -> char {
+        match self.styles.get(label).and_then(|s| s.marker) {
+            Some(marker) => marker,
+            None => label.chars().next().expect("label should not be empty"),
+        }
+    }
+
+    /// Sorted labels of the datasets, error columns excluded
+    pub fn labels(&self) -> Vec<&String> {
+        let mut labels: Vec<&String> = self
+            .dataset
+            .keys()
+            .filter(|label| !self.is_error_column(label))
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Keep only the datasets whose label matches the predicate
+    pub fn retain(&mut self, f: impl Fn(&str) -> bool) {
+        self.dataset.retain(|label, _| f(label));
+        self.errors.retain(|label, _| f(label));
+    }
+
+    /// Get the x value of a category, registering it if needed
+    fn get_category(&mut self, category: String) -> f64 {
+        let idx = match self.categories.iter().position(|c| *c == category) {
+            Some(idx) => idx,
+            None => {
+                self.categories.push(category);
+                self.categories.len() - 1
+            }
+        };
+        idx as f64
+    }
+
+    /// Consider x values as unix timestamps
+    ///
+    /// Does nothing if x values are already dates
+    pub fn set_time_axis(&mut self, unit: TimeUnit) {
+        if self.time_axis || !self.categories.is_empty() {
+            return;
+        }
+        for points in self.dataset.values_mut() {
+            for point in points.iter_mut() {
+                point.0 /= unit.per_second();
+            }
+        }
+        self.time_axis = true;
+        self.time_unit = Some(unit);
+    }
+
+    /// Whether x values are dates, as seconds since 1970-01-01T00:00:00Z
+    pub fn is_time_axis(&self) -> bool {
+        self.time_axis
+    }
+
+    /// Set the format of time labels (see [format_time])
+    pub fn set_time_format(&mut self, format: String) {
+        self.time_format = Some(format);
+    }
+
+    /// Set how densities are rendered
+    pub fn set_shading(&mut self, shading: Shading) {
+        self.shading = shading;
+    }
+
+    /// Set how cells hit by several points are rendered
+    pub fn set_overplot(&mut self, overplot: Overplot) {
+        self.overplot = overplot;
+    }
+
+    /// Set the model fitted and drawn over each dataset of point plots
+    pub fn set_trend(&mut self, fit: Option<Fit>) {
+        self.trend = fit;
+    }
+
+    /// Model fitted on each dataset, sorted by label
+    ///
+    /// Datasets without enough points are skipped
+    pub fn get_trends(&self) -> Vec<(String, Trend)> {
+        let Some(fit) = self.trend else {
+            return Vec::new();
+        };
+        let mut trends: Vec<(String, Trend)> = self
+            .dataset
+            .iter()
+            .filter(|(label, _)| !self.is_error_column(label))
+            .filter_map(|(label, points)| Some((label.clone(), Trend::from_points(points, fit)?)))
+            .collect();
+        trends.sort_by(|a, b| a.0.cmp(&b.0));
+        trends
+    }
+
+    /// Smooth a specific dataset, or all datasets without a specific smoothing,
+    /// before drawing points
+    pub fn set_smoothing(&mut self, label: Option<&str>, smoothing: Smoothing) {
+        self.smoothing.insert(label.map(String::from), smoothing);
+    }
+
+    /// Set whether raw points are drawn along smoothed datasets
+    pub fn set_show_raw(&mut self, show_raw: bool) {
+        self.show_raw = show_raw;
+    }
+
+    /// Smoothed points of a dataset, None if it is not smoothed
+    pub fn get_smoothed(&self, label: &str) -> Option<Vec<(f64, f64)>> {
+        let points = self.dataset.get(label)?;
+        let smoothing = self
+            .smoothing
+            .get(&Some(label.into()))
+            .or(self.smoothing.get(&None))?;
+        Some(smoothing.apply(points))
+    }
+
+    /// Scale histograms so that their area is 1, as density estimates
+    pub fn set_histogram_density(&mut self, density: bool) {
+        self.histogram_density = density;
+    }
+
+    /// Set how large datasets are reduced before drawing points
+    pub fn set_downsampling(&mut self, downsampling: Downsampling) {
+        self.downsampling = downsampling;
+    }
+
+    /// Estimate quantiles of boxplots, CDF and statistics with a [QuantileSketch]
+    /// for datasets larger than threshold
+    pub fn set_sketch_threshold(&mut self, threshold: Option<usize>) {
+        self.sketch_threshold = threshold;
+    }
+
+    fn uses_sketch(&self, values: &[f64]) -> bool {
+        self.sketch_threshold.is_some_and(|t| values.len() > t)
+    }
+
+    /// Set how bars are laid out
+    pub fn set_bar_layout(&mut self, mode: BarMode, horizontal: bool) {
+        self.bar_mode = mode;
+        self.horizontal = horizontal;
+    }
+
+    /// Set the kernel used for density estimates
+    pub fn set_kernel(&mut self, kernel: Kernel) {
+        self.kernel = kernel;
+    }
+
+    /// Set how the bandwidth of density estimates is chosen
+    pub fn set_bandwidth(&mut self, bandwidth: Bandwidth) {
+        self.bandwidth = bandwidth;
+    }
+
+    /// Set the distribution quantiles are compared to in Q-Q plots
+    ///
+    /// A reference series must be one of the datasets
+    pub fn set_qq_reference(&mut self, reference: QQReference) -> Result<(), DatasetError> {
+        if let QQReference::Series(label) = &reference
+            && !self.dataset.contains_key(label)
+            && !self.columns.contains(label)
+        {
+            return Err(DatasetError::InvalidData(format!(
+                "unknown Q-Q reference {label}: expected normal, exponential, uniform or a dataset"
+            )));
+        }
+        self.qq_reference = reference;
+        Ok(())
+    }
+
     pub fn draw_into(&self, canvas: &mut Canvas, kind: PlotKind) -> Result<(), CanvasError> {
+        // hidden datasets and error columns are not drawn
+        let shown = |label: &str| self.style(label).visible && !self.is_error_column(label);
+        if self.dataset.keys().any(|label| !shown(label)) {
+            let mut visible = self.clone();
+            visible.retain(shown);
+            return visible.draw_into(canvas, kind);
+        }
         match kind {
             PlotKind::Point => self.draw_point(canvas),
             PlotKind::Boxplot => self.draw_boxplot(canvas),
-            PlotKind::CDF => self.draw_cdf(canvas),
+            PlotKind::CDF => self.draw_cdf(canvas, false),
+            PlotKind::CCDF => self.draw_cdf(canvas, true),
             PlotKind::Histogram => self.draw_histogram(canvas),
+            PlotKind::Density => self.draw_density(canvas),
+            PlotKind::Violin => self.draw_violin(canvas),
+            PlotKind::QQ => self.draw_qq(canvas),
+            PlotKind::Heatmap => self.draw_heatmap(canvas),
+            PlotKind::Bar => self.draw_bar(canvas),
+        }?;
+        if self.grid {
+            canvas.draw_grid()?;
         }
+        self.draw_annotations(canvas)
     }
 
     fn draw_point(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
         // TODO check if range already set
-        self.reset_canvas_range(canvas)?;
+        self.reset_canvas_range(canvas, true)?;
+        canvas.draw_secondary_axis(&self.secondary.join(", "))?;
         canvas.draw_axes()?;
+        let y2 = canvas.secondary_map();
+        let data = match y2 {
+            Some(map) => Cow::Owned(self.map_secondary(map)),
+            None => Cow::Borrowed(self),
+        };
+
+        for (label, errors) in &data.errors {
+            let points = data.dataset.get(label).into_iter().flatten();
+            for (error, &point) in zip(errors, points.clone()) {
+                error.draw_into(canvas, point)?;
+            }
+            // y intervals are also shaded in SVG output
+            let intervals: Vec<(f64, f64, f64)> = zip(errors, points)
+                .filter_map(|(error, point)| error.y.map(|(low, high)| (point.0, low, high)))
+                .collect();
+            canvas.set_pen(self.style(label).color);
+            canvas.add_band(&intervals);
+            canvas.set_pen(None);
+        }
+
+        // fitted on the values, then mapped
+        for (label, trend) in self.get_trends() {
+            match y2.filter(|_| self.is_secondary(&label)) {
+                Some(map) => canvas.draw_curve(|x| map.apply(trend.get_value(x)), '.')?,
+                None => trend.draw_into(canvas, '.')?,
+            }
+        }
 
         // TODO add labels
-        for (label, points) in self.dataset.iter() {
+        let mut hits = Hits::new(canvas);
+        for (series, (label, points)) in data.dataset.iter().enumerate() {
             // TODO: use correct labels
-            let l = label.bytes().next().unwrap();
-            for point in points {
-                if point.0.is_nan() || point.1.is_nan() {
-                    continue;
+            let l = self.marker(label);
+            let smoothed = data.get_smoothed(label);
+            if smoothed.is_some() && self.show_raw {
+                for &(x, y) in points {
+                    // points out of the canvas are skipped
+                    let _ = canvas.draw_value(x, y, '.');
                 }
-                canvas.draw_value(point.0, point.1, l)?;
             }
+            let points = self.downsampling.apply(
+                smoothed.as_deref().unwrap_or(points),
+                canvas.x_range,
+                canvas.width,
+            );
+            canvas.set_pen(self.style(label).color);
+            canvas.draw_polyline(&points, self.style(label).line, l)?;
+            for point in points.iter() {
+                hits.add(canvas, point.0, point.1, series, l);
+            }
+            canvas.set_pen(None);
+        }
+        hits.draw_into(canvas, self.overplot, self.shading)?;
+        if self.time_axis {
+            self.draw_time_labels(canvas)?;
         }
         Ok(())
     }
 
+    /// Write dates on the last line of the canvas, at calendar boundaries
+    fn draw_time_labels(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
+        let (start, end) = canvas.x_range;
+        let (ticks, format) = time_ticks(start, end, canvas.width / 12);
+        let format = self.time_format.as_deref().unwrap_or(format);
+        let line = canvas.height - 1;
+        let mut free = 0;
+        for t in ticks {
+            let column = get_cell(t, start, end, canvas.width)?;
+            let label = format_time(t, format);
+            if column < free || column + label.len() > canvas.width {
+                continue;
+            }
+            canvas.draw_text(line, column, &label)?;
+            free = column + label.len() + 1;
+        }
+        Ok(())
+    }
+
+    fn draw_heatmap(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
+        self.reset_canvas_range(canvas, false)?;
+        canvas.draw_axes()?;
+        let heatmap = Heatmap::from_points(self.dataset.values().flatten(), canvas);
+        heatmap.draw_into(canvas, self.shading)
+    }
+
     fn draw_boxplot(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
         // TODO set canvas size
         let mut height = 0;
         for dataset in self.dataset.values() {
-            let q = Quantiles::from_slice(&dataset.iter().map(|x| x.1).collect::<Vec<_>>());
+            let y: Vec<f64> = dataset.iter().map(|x| x.1).collect();
+            let q = if self.uses_sketch(&y) {
+                Quantiles::from_sketch(&y, &QuantileSketch::from_slice(&y))
+                    .ok_or(CanvasError::NoData)?
+            } else {
+                Quantiles::from_slice(&y)
+            };
             q.draw_into(canvas, height)?;
             height += 4
         }
         Ok(())
     }
 
-    fn draw_cdf(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
-        // TODO: set canvas size
-        canvas.y_range = (-0.1, 1.1);
-        canvas.draw_axes()?;
-        for (label, data) in &self.dataset {
-            let cdf = CDF::from_vec(data.iter().map(|x| x.1).collect());
-            cdf.draw_into(
-                canvas,
-                label.bytes().next().expect("label should not be empty"),
-            )?
-        }
-        Ok(())
+    fn draw_cdf(&self, canvas: &mut Canvas, complementary: bool) -> Result<(), CanvasError> {
+        let cdfs: Vec<(char, Option<Color>, CDF)> = self
+            .dataset
+            .iter()
+            .map(|(label, data)| {
+                let y: Vec<f64> = data.iter().map(|x| x.1).collect();
+                let cdf = if self.uses_sketch(&y) {
+                    QuantileSketch::from_slice(&y).cdf()
+                } else {
+                    CDF::from_vec(y)
+                };
+                (self.marker(label), self.style(label).color, cdf)
+            })
+            .filter(|(_, _, cdf)| !cdf.steps.is_empty())
+            .collect();
+        let nb = self.dataset.values().map(|d| d.len()).max().unwrap_or(1);
+        draw_cdfs(canvas, cdfs, complementary, nb)
     }
 
     fn draw_histogram(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
-        let hists: HashMap<String, Histogram> = self
+        let hists: Vec<(char, Option<Color>, Histogram)> = self
             .dataset
             .iter()
             .map(|(label, dataset)| {
                 (
-                    label.clone(),
+                    self.marker(label),
+                    self.style(label).color,
                     Histogram::from_vec(
                         dataset
                             .iter()
@@ -574,65 +1496,330 @@ impl DataSet {
                     ),
                 )
             })
+            .map(|(symbole, color, h)| {
+                if self.histogram_density {
+                    (symbole, color, h.into_density())
+                } else {
+                    (symbole, color, h)
+                }
+            })
             .collect();
 
-        // set canvas ranges
-        let (x_min, x_max) = hists
-            .values()
-            .map(|h| {
+        draw_histograms(canvas, &hists)
+    }
+
+    fn draw_density(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
+        let densities: Vec<(&String, Density)> = self
+            .dataset
+            .iter()
+            .map(|(label, data)| {
+                let values = data.iter().map(|x| x.1).collect();
                 (
-                    *h.bins.first().expect("dataset should not be empty"),
-                    *h.bins.last().unwrap(),
+                    label,
+                    Density::from_vec(values, self.kernel, self.bandwidth),
                 )
             })
+            .collect();
+        let (x_min, x_max) = densities
+            .iter()
+            .filter_map(|(_, d)| d.range())
             .reduce(|(a, b), (c, d)| (a.min(c), b.max(d)))
-            .unwrap();
-        let y_max = hists
-            .values()
-            .map(|h| h.values.clone().into_iter().fold(0, |acc, x| acc.max(x)))
-            .reduce(|acc, b| acc.max(b))
-            .unwrap() as f64;
-        let y_min = -y_max / 20.0;
-        canvas.x_range = (x_min, x_max);
-        canvas.y_range = (y_min, y_max);
-
-        for (l, h) in hists.iter() {
-            h.draw_into(
-                canvas,
-                l.bytes()
-                    .next()
-                    .expect("label should be at least one letter long"),
-            )?
+            .ok_or(CanvasError::NoData)?;
+        canvas.set_x_range(x_min, x_max);
+        let delta = (canvas.x_range.1 - canvas.x_range.0) / canvas.width as f64;
+        let y_max = densities
+            .iter()
+            .flat_map(|(_, d)| (0..canvas.width).map(|c| d.get_value(x_min + delta * c as f64)))
+            .fold(0.0, f64::max);
+        canvas.set_y_bounds(-y_max / 20.0, y_max * 1.05);
+        canvas.draw_axes()?;
+
+        for (label, d) in densities {
+            canvas.set_pen(self.style(label).color);
+            d.draw_into(canvas, self.marker(label))?
         }
+        canvas.set_pen(None);
         Ok(())
     }
 
-    fn reset_canvas_range(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
-        let mut points = self.dataset.values().flatten();
-        let first = points.next().ok_or(CanvasError::NoData)?;
-        let (x_min, x_max, y_min, y_max) = points.fold(
-            (first.0, first.0, first.1, first.1),
-            |(x0, x1, y0, y1), p| (x0.min(p.0), x1.max(p.0), y0.min(p.1), y1.max(p.1)),
-        );
+    fn draw_violin(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
+        let mut violins: Vec<(&String, Violin)> = self
+            .dataset
+            .iter()
+            .filter_map(|(label, data)| {
+                let values = data.iter().map(|x| x.1).collect();
+                Violin::from_vec(values, self.kernel, self.bandwidth).map(|v| (label, v))
+            })
+            .collect();
+        violins.sort_by(|a, b| a.0.cmp(b.0));
+        let (x_min, x_max) = violins
+            .iter()
+            .map(|(_, v)| v.range())
+            .reduce(|(a, b), (c, d)| (a.min(c), b.max(d)))
+            .ok_or(CanvasError::NoData)?;
         canvas.set_x_range(x_min, x_max);
-        canvas.set_y_range(y_min, y_max);
+
+        if violins.len() > canvas.height {
+            return Err(CanvasError::OutOfRange(format!(
+                "{} violins do not fit in {} lines",
+                violins.len(),
+                canvas.height
+            )));
+        }
+        // each violin takes an odd number of lines, centered on its quartiles,
+        // a single line only showing the quartiles
+        let slot = canvas.height / violins.len();
+        let lines = slot - (1 - slot % 2);
+        for (idx, (label, v)) in violins.iter().enumerate() {
+            canvas.set_pen(self.style(label).color);
+            v.draw_into(canvas, idx * slot, lines, self.marker(label))?
+        }
+        canvas.set_pen(None);
+        Ok(())
+    }
+
+    fn draw_qq(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
+        let reference: Option<Vec<f64>> = match &self.qq_reference {
+            QQReference::Series(label) => Some(
+                self.dataset
+                    .get(label)
+                    .ok_or(CanvasError::NoData)?
+                    .iter()
+                    .map(|x| x.1)
+                    .collect(),
+            ),
+            _ => None,
+        };
+        let plots: Vec<(&String, QQ)> = self
+            .dataset
+            .iter()
+            .filter(|(label, _)| self.qq_reference != QQReference::Series(label.to_string()))
+            .map(|(label, data)| {
+                let values = data.iter().map(|x| x.1).collect();
+                let qq = QQ::from_vec(values, &self.qq_reference, reference.as_deref());
+                (label, qq)
+            })
+            .collect();
+
+        // same range on both axes so that the reference line is the diagonal
+        let (min, max) = plots
+            .iter()
+            .flat_map(|(_, qq)| qq.points())
+            .flat_map(|&(x, y)| [x, y])
+            .filter(|x| x.is_finite())
+            .fold(None, |acc: Option<(f64, f64)>, x| match acc {
+                Some((mi, ma)) => Some((mi.min(x), ma.max(x))),
+                None => Some((x, x)),
+            })
+            .ok_or(CanvasError::NoData)?;
+        let (min, max) = if min < max {
+            (min, max)
+        } else {
+            (min - 1.0, max + 1.0)
+        };
+        canvas.set_x_range(min, max);
+        canvas.set_y_range(min, max);
+        canvas.draw_axes()?;
+        canvas.draw_curve(|x| x, '.')?;
+
+        for (label, qq) in plots {
+            canvas.set_pen(self.style(label).color);
+            qq.draw_into(canvas, self.marker(label))?
+        }
+        canvas.set_pen(None);
         Ok(())
     }
 
+    fn draw_bar(&self, canvas: &mut Canvas) -> Result<(), CanvasError> {
+        // numerical x: each distinct value is a category
+        let xs: Vec<f64> = if self.categories.is_empty() {
+            let mut xs: Vec<f64> = self
+                .dataset
+                .values()
+                .flatten()
+                .map(|p| p.0)
+                .filter(|x| !x.is_nan())
+                .collect();
+            xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            xs.dedup();
+            xs
+        } else {
+            (0..self.categories.len()).map(|x| x as f64).collect()
+        };
+        let names = if self.time_axis && !xs.is_empty() {
+            let (_, format) = time_ticks(xs[0], xs[xs.len() - 1], xs.len());
+            let format = self.time_format.as_deref().unwrap_or(format);
+            xs.iter().map(|&x| format_time(x, format)).collect()
+        } else if self.categories.is_empty() {
+            xs.iter().map(|x| x.to_string()).collect()
+        } else {
+            self.categories.clone()
+        };
+        let mut bars = Bars::new(names);
+        let mut labels: Vec<&String> = self.dataset.keys().collect();
+        labels.sort();
+        for label in labels {
+            let mut values = vec![f64::NAN; xs.len()];
+            for &(x, y) in &self.dataset[label] {
+                if y.is_nan() {
+                    continue;
+                }
+                if let Ok(idx) = xs.binary_search_by(|a| a.partial_cmp(&x).unwrap()) {
+                    values[idx] = if values[idx].is_nan() {
+                        y
+                    } else {
+                        values[idx] + y
+                    };
+                }
+            }
+            bars.add_colored_series(self.marker(label), self.style(label).color, values);
+        }
+        if self.horizontal {
+            bars.draw_horizontal_into(canvas, self.bar_mode)
+        } else {
+            bars.draw_into(canvas, self.bar_mode)
+        }
+    }
+
+    /// Set ranges fitting the datasets, the ones of the secondary y axis
+    /// getting their own range if secondary is true
+    fn reset_canvas_range(&self, canvas: &mut Canvas, secondary: bool) -> Result<(), CanvasError> {
+        let (primary, secondary) = if secondary && !self.secondary.is_empty() {
+            (
+                self.extent(|label| !self.is_secondary(label)),
+                self.extent(|label| self.is_secondary(label)),
+            )
+        } else {
+            (self.extent(|_| true), None)
+        };
+        let (x_min, x_max, y_min, y_max) = match (primary, secondary) {
+            (Some(p), Some(s)) => (p.0.min(s.0), p.1.max(s.1), p.2, p.3),
+            (Some(extent), None) | (None, Some(extent)) => extent,
+            (None, None) => return Err(CanvasError::NoData),
+        };
+        // a single value is centered
+        if x_min < x_max {
+            canvas.set_x_range(x_min, x_max);
+        } else {
+            canvas.set_x_range(x_min - 1.0, x_max + 1.0);
+        }
+        if y_min < y_max {
+            canvas.set_y_range(y_min, y_max);
+        } else {
+            canvas.set_y_range(y_min - 1.0, y_max + 1.0);
+        }
+        canvas.y2_range = None;
+        if let Some((_, _, y_min, y_max)) = secondary {
+            if y_min < y_max {
+                canvas.set_y2_range(y_min, y_max);
+            } else {
+                canvas.set_y2_range(y_min - 1.0, y_max + 1.0);
+            }
+        }
+        Ok(())
+    }
+
+    /// (x_min, x_max, y_min, y_max) of the selected datasets, with their uncertainties
+    fn extent(&self, selected: impl Fn(&str) -> bool) -> Option<(f64, f64, f64, f64)> {
+        // error bars are drawn as their extreme points
+        let extremes: Vec<(f64, f64)> = self
+            .errors
+            .iter()
+            .filter(|(label, _)| selected(label))
+            .flat_map(|(label, errors)| zip(errors, self.dataset.get(label).into_iter().flatten()))
+            .flat_map(|(e, &(x, y))| {
+                let (x0, x1) = e.x.unwrap_or((x, x));
+                let (y0, y1) = e.y.unwrap_or((y, y));
+                [(x0, y0), (x1, y1)]
+            })
+            .collect();
+        let mut points = self
+            .dataset
+            .iter()
+            .filter(|(label, _)| selected(label))
+            .flat_map(|(_, points)| points)
+            .chain(&extremes);
+        let first = points.next()?;
+        Some(points.fold(
+            (first.0, first.0, first.1, first.1),
+            |(x0, x1, y0, y1), p| (x0.min(p.0), x1.max(p.0), y0.min(p.1), y1.max(p.1)),
+        ))
+    }
+
     /// Get quantiles for each dataset
-    fn get_quantiles(&self) -> HashMap<String, Option<Quantiles>> {
-        todo!()
+    /// Return None for datasets without any valid value
+    pub fn get_quantiles(&self) -> HashMap<String, Option<Quantiles>> {
+        self.dataset
+            .iter()
+            .map(|(label, points)| {
+                let y: Vec<f64> = points.iter().map(|p| p.1).collect();
+                let q = y
+                    .iter()
+                    .any(|a| !a.is_nan())
+                    .then(|| Quantiles::from_slice(&y));
+                (label.clone(), q)
+            })
+            .collect()
+    }
+
+    /// Get summary statistics for each dataset, sorted by label
+    pub fn get_stats(&self) -> Vec<(String, Stats)> {
+        let mut stats: Vec<(String, Stats)> = self
+            .dataset
+            .iter()
+            .filter(|(label, _)| !self.is_error_column(label))
+            .map(|(label, points)| {
+                let y: Vec<f64> = points.iter().map(|p| p.1).collect();
+                if self.uses_sketch(&y) {
+                    (label.clone(), Stats::approximate(&y))
+                } else {
+                    (label.clone(), Stats::from_slice(&y))
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.0.cmp(&b.0));
+        stats
     }
 
     /// Get cumulative distribution for each dataset
     /// Return points where the distribution changes
-    fn get_cumulatives(&self) -> HashMap<String, Option<Vec<(f64, f64)>>> {
-        todo!()
+    /// Return None for datasets without any valid value
+    pub fn get_cumulatives(&self) -> HashMap<String, Option<Vec<(f64, f64)>>> {
+        self.dataset
+            .iter()
+            .map(|(label, points)| {
+                let cdf = CDF::from_vec(points.iter().map(|p| p.1).collect());
+                (label.clone(), (!cdf.steps.is_empty()).then(|| cdf.steps()))
+            })
+            .collect()
     }
 }
 
+/// Index of a quantile, from 0 for the minimum to length - 1 for the maximum
 fn get_index(quantile: f64, length: usize) -> f64 {
-    quantile * length as f64
+    quantile * (length - 1) as f64
+}
+
+/// Get the value at a specific quantile of sorted, non empty values,
+/// interpolating linearly between the closest ranks
+fn get_quantile(sorted: &[f64], quantile: f64) -> f64 {
+    let idx = get_index(quantile, sorted.len());
+    get_value(sorted, idx).expect("index should be in range")
+}
+
+/// Get the mean and the sample standard deviation of values, NaN excluded
+///
+/// The deviation of a single value is 0, both are NaN without values
+fn get_mean_std_dev(values: impl Iterator<Item = f64> + Clone) -> (f64, f64) {
+    let values = values.filter(|x| !x.is_nan());
+    let count = values.clone().count();
+    let mean = values.clone().sum::<f64>() / count as f64;
+    let std_dev = match count {
+        0 => f64::NAN,
+        1 => 0.0,
+        _ => (values.map(|x| (x - mean).powi(2)).sum::<f64>() / (count - 1) as f64).sqrt(),
+    };
+    (mean, std_dev)
 }
 
 /// Get value at specific non-integer index
@@ -652,6 +1839,76 @@ fn get_value(x: &[f64], idx: f64) -> Option<f64> {
     Some((1.0 - f) * x[i] + f * (x[i + 1]))
 }
 
+/// Draw histograms with their symbol and color, the canvas range being set to fit all of them
+fn draw_histograms(
+    canvas: &mut Canvas,
+    hists: &[(char, Option<Color>, Histogram)],
+) -> Result<(), CanvasError> {
+    // set canvas ranges
+    let (x_min, x_max) = hists
+        .iter()
+        .map(|(_, _, h)| {
+            (
+                *h.bins.first().expect("dataset should not be empty"),
+                *h.bins.last().unwrap(),
+            )
+        })
+        .reduce(|(a, b), (c, d)| (a.min(c), b.max(d)))
+        .ok_or(CanvasError::NoData)?;
+    let y_max = hists
+        .iter()
+        .map(|(_, _, h)| h.max_value())
+        .fold(0.0, f64::max);
+    let y_min = -y_max / 20.0;
+    canvas.set_x_bounds(x_min, x_max);
+    canvas.set_y_bounds(y_min, y_max);
+
+    for (symbole, color, h) in hists {
+        canvas.set_pen(*color);
+        h.draw_into(canvas, *symbole)?
+    }
+    canvas.set_pen(None);
+    Ok(())
+}
+
+/// Draw distributions with their symbol and color, nb being the size of the largest dataset
+fn draw_cdfs(
+    canvas: &mut Canvas,
+    cdfs: Vec<(char, Option<Color>, CDF)>,
+    complementary: bool,
+    nb: usize,
+) -> Result<(), CanvasError> {
+    let (x_min, x_max) = cdfs
+        .iter()
+        .map(|(_, _, cdf)| (cdf.steps[0].0, cdf.steps[cdf.steps.len() - 1].0))
+        .reduce(|(a, b), (c, d)| (a.min(c), b.max(d)))
+        .ok_or(CanvasError::NoData)?;
+    if x_min < x_max {
+        canvas.set_x_range(x_min, x_max);
+    } else {
+        canvas.set_x_range(x_min - 1.0, x_max + 1.0);
+    }
+    if complementary {
+        // lowest non null probability is reached before the last value
+        canvas.log_y = true;
+        canvas.set_y_bounds(0.5 / nb as f64, 1.5);
+    } else {
+        canvas.set_y_bounds(-0.1, 1.1);
+    }
+    canvas.draw_axes()?;
+    for (symbole, color, cdf) in cdfs {
+        let cdf = if complementary {
+            cdf.complementary()
+        } else {
+            cdf
+        };
+        canvas.set_pen(color);
+        cdf.draw_into(canvas, symbole)?
+    }
+    canvas.set_pen(None);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -674,6 +1931,14 @@ mod tests {
         assert!(get_value(&v, 0.25).is_none());
     }
 
+    #[test]
+    fn mean_std_dev() {
+        let v = [2.0, f64::NAN, 4.0, 6.0];
+        assert_eq!(get_mean_std_dev(v.iter().copied()), (4.0, 2.0));
+        assert_eq!(get_mean_std_dev([5.0].into_iter()), (5.0, 0.0));
+        assert!(get_mean_std_dev([f64::NAN].into_iter()).1.is_nan());
+    }
+
     #[test]
     fn quantiles() {
         let v = [1.0, 3.0, 4.0, 0.0, 2.0];
@@ -682,13 +1947,21 @@ mod tests {
             q,
             Quantiles {
                 min: 0.0,
-                q1: 1.25,
-                q2: 2.5,
-                q3: 3.75,
+                q1: 1.0,
+                q2: 2.0,
+                q3: 3.0,
                 max: 4.0,
                 outliers: Vec::new(),
             }
         );
+        // no spread between the quartiles
+        let q = Quantiles::from_slice(&[5.0, 5.0, 5.0]);
+        assert_eq!((q.min(), q.median(), q.max()), (5.0, 5.0, 5.0));
+        assert!(q.outliers().is_empty());
+        let q = Quantiles::from_slice(&[5.0, 5.0, 5.0, 9.0]);
+        assert_eq!((q.max(), q.outliers()), (5.0, &[9.0][..]));
+        let q = Quantiles::from_slice(&[7.0]);
+        assert_eq!((q.min(), q.q1(), q.q3(), q.max()), (7.0, 7.0, 7.0, 7.0));
     }
 
     #[test]
@@ -703,6 +1976,212 @@ mod tests {
         assert!(dataset.dataset.len() == 3);
     }
 
+    #[test]
+    fn dataset_categorical_csv() {
+        let text = "service,errors,warnings\napi,3,1\ndb,0,2\n\napi,1,1\n";
+        let dataset = DataSet::from_csv(text).unwrap();
+        assert_eq!(dataset.categories(), ["api", "db"]);
+        assert_eq!(
+            dataset.dataset["errors"],
+            [(0.0, 3.0), (1.0, 0.0), (0.0, 1.0)]
+        );
+        // a typo does not turn numbers into categories
+        match DataSet::from_csv("x,a\n0,1\n1,2\n2.O,3\n") {
+            Err(DatasetError::InvalidData(msg)) => assert!(msg.starts_with("row 3: x value 2.O")),
+            _ => panic!("mixed x values should be rejected"),
+        }
+        assert!(DataSet::from_csv("x,a\napi,1\n2,2\n").is_err());
+        match DataSet::from_csv("t,a\n2024-03-01,1\n2024-03-02,1\nlater,2\n") {
+            Err(DatasetError::InvalidData(msg)) => assert!(msg.contains("later is not a date")),
+            _ => panic!("dates followed by a category should be rejected"),
+        }
+    }
+
+    #[test]
+    fn dataset_time_csv() {
+        let text = "time,a\n2024-03-01T12:00:00Z,1\n2024-03-01T12:00:30+00:00,2\n";
+        let dataset = DataSet::from_csv(text).unwrap();
+        assert!(dataset.is_time_axis());
+        assert!(dataset.categories().is_empty());
+        assert_eq!(dataset.dataset["a"][1], (1709294430.0, 2.0));
+
+        let mut dataset = DataSet::from_csv("t,a\n1709294400000,1\n").unwrap();
+        dataset.set_time_axis(TimeUnit::Milliseconds);
+        assert_eq!(dataset.dataset["a"][0], (1709294400.0, 1.0));
+    }
+
+    #[test]
+    fn dataset_errors() {
+        let text = "x,mean,sd,low,high\n0,1,0.5,0,3\n1,2,1,1,4\n";
+        let mut dataset = DataSet::from_csv(text).unwrap();
+        dataset
+            .set_y_error("mean", ErrorSpec::Symmetric("sd".into()))
+            .unwrap();
+        dataset
+            .set_x_error("mean", ErrorSpec::Bounds("low".into(), "high".into()))
+            .unwrap();
+        assert_eq!(dataset.labels(), ["mean"]);
+        assert_eq!(
+            dataset.errors["mean"][1],
+            ErrorBar {
+                x: Some((1.0, 4.0)),
+                y: Some((1.0, 3.0))
+            }
+        );
+        assert!(
+            dataset
+                .set_y_error("median", ErrorSpec::Symmetric("sd".into()))
+                .is_err()
+        );
+
+        // a column holding the uncertainty of several datasets, along both axes
+        let text = "x,a,b,sd\n0,1,5,0.5\n1,2,6,1\n";
+        let mut dataset = DataSet::from_csv(text).unwrap();
+        for label in ["a", "b"] {
+            dataset
+                .set_y_error(label, ErrorSpec::Symmetric("sd".into()))
+                .unwrap();
+        }
+        dataset
+            .set_x_error("a", ErrorSpec::Symmetric("sd".into()))
+            .unwrap();
+        assert_eq!(dataset.labels(), ["a", "b"]);
+        assert_eq!(dataset.errors["b"][1].y, Some((5.0, 7.0)));
+        // replaced uncertainty, whose column is plotted again
+        dataset
+            .set_y_error("b", ErrorSpec::Bounds("a".into(), "sd".into()))
+            .unwrap();
+        dataset
+            .set_y_error("b", ErrorSpec::Bounds("a".into(), "b".into()))
+            .unwrap();
+        assert_eq!(dataset.error_specs.len(), 3);
+        assert_eq!(dataset.errors["b"][1].y, Some((2.0, 6.0)));
+        assert_eq!(dataset.errors["a"][1].x, Some((0.0, 2.0)));
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        assert!(!canvas.to_string().contains('s'));
+    }
+
+    #[test]
+    fn dataset_trends() {
+        let text = "x,a,b\n0,1,3\n1,3,2\n2,5,1\n";
+        let mut dataset = DataSet::from_csv(text).unwrap();
+        assert!(dataset.get_trends().is_empty());
+        dataset.set_trend(Some(Fit::Linear));
+        let trends = dataset.get_trends();
+        assert_eq!(trends.len(), 2);
+        assert_eq!(trends[1].0, "b");
+        assert!((trends[1].1.coefficients()[1] + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn dataset_many_violins() {
+        let header: Vec<String> = (0..20).map(|i| format!("s{i}")).collect();
+        let text = format!(
+            "x,{}\n0,{}\n1,{}\n",
+            header.join(","),
+            "1,".repeat(19) + "1",
+            "2,".repeat(19) + "2"
+        );
+        let dataset = DataSet::from_csv(&text).unwrap();
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Violin).unwrap();
+        // one line per violin, showing its quartiles
+        let text = canvas.to_string();
+        assert_eq!(text.lines().filter(|l| l.contains('|')).count(), 20);
+        let mut canvas = Canvas::from_size(10, 80);
+        assert!(dataset.draw_into(&mut canvas, PlotKind::Violin).is_err());
+    }
+
+    #[test]
+    fn dataset_unicode_labels() {
+        let text = "x,été,ßeta\n1,1,4\n2,2,5\n3,3,6\n";
+        let dataset = DataSet::from_csv(text).unwrap();
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        let text = canvas.to_string();
+        assert_eq!(text.matches('é').count(), 3);
+        assert_eq!(text.matches('ß').count(), 3);
+        // axes in the bottom left corner
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[24].starts_with("└────┼"));
+        assert!(lines[..24].iter().all(|l| l.starts_with(['│', '┼'])));
+    }
+
+    #[test]
+    fn dataset_smoothing() {
+        let text = "x,a,b\n0,0,1\n1,2,1\n2,4,1\n";
+        let mut dataset = DataSet::from_csv(text).unwrap();
+        assert_eq!(dataset.get_smoothed("a"), None);
+        dataset.set_smoothing(None, Smoothing::Sma(3));
+        dataset.set_smoothing(Some("b"), Smoothing::Ema(0.5));
+        assert_eq!(
+            dataset.get_smoothed("a"),
+            Some(vec![(0.0, 1.0), (1.0, 2.0), (2.0, 3.0)])
+        );
+        assert_eq!(dataset.get_smoothed("b").unwrap()[2], (2.0, 1.0));
+    }
+
+    #[test]
+    fn dataset_append() {
+        let mut dataset = DataSet::from_csv("x,mean,sd\n").unwrap();
+        dataset
+            .set_y_error("mean", ErrorSpec::Symmetric("sd".into()))
+            .unwrap();
+        for line in ["0,1,0.5", "", "1,2,1", "2,4,1"] {
+            dataset.append_csv_line(line).unwrap();
+        }
+        assert_eq!(dataset.labels(), ["mean"]);
+        assert_eq!(dataset.dataset["mean"].len(), 3);
+        assert_eq!(dataset.errors["mean"][1].y, Some((1.0, 3.0)));
+        assert!(dataset.append_csv_line("x,1,1").is_err());
+
+        dataset.apply_window(Window::Points(2));
+        assert_eq!(dataset.dataset["mean"], [(1.0, 2.0), (2.0, 4.0)]);
+        assert_eq!(dataset.errors["mean"][0].y, Some((1.0, 3.0)));
+        dataset.apply_window(Window::Span(0.5));
+        assert_eq!(dataset.dataset["mean"], [(2.0, 4.0)]);
+
+        let mut dataset = DataSet::from_csv("date,a\n").unwrap();
+        dataset.append_csv_line("2024-03-01,1").unwrap();
+        assert!(dataset.is_time_axis());
+    }
+
+    #[test]
+    fn dataset_quantiles() {
+        let mut dataset = DataSet::default();
+        dataset.add_points("a".into(), vec![(0.0, 1.0), (1.0, 3.0), (2.0, 2.0)]);
+        dataset.add_points("b".into(), vec![(0.0, f64::NAN)]);
+        let q = dataset.get_quantiles();
+        assert_eq!(q["a"].as_ref().unwrap().median(), 2.0);
+        assert!(q["b"].is_none());
+    }
+
+    #[test]
+    fn cdf_values() {
+        let cdf = CDF::from_vec(vec![2.0, 1.0, f64::NAN, 2.0, 3.0]);
+        assert_eq!(cdf.steps(), vec![(1.0, 0.25), (2.0, 0.75), (3.0, 1.0)]);
+        assert_eq!(cdf.get_value(0.0), 0.0);
+        assert_eq!(cdf.get_value(2.0), 0.75);
+        assert_eq!(cdf.get_value(2.5), 0.75);
+        assert_eq!(cdf.get_value(4.0), 1.0);
+
+        let ccdf = cdf.complementary();
+        assert_eq!(ccdf.get_value(0.0), 1.0);
+        assert_eq!(ccdf.get_value(2.5), 0.25);
+        assert_eq!(ccdf.get_value(4.0), 0.0);
+    }
+
+    #[test]
+    fn dataset_cumulatives() {
+        let mut dataset = DataSet::default();
+        dataset.add_points("a".into(), vec![(0.0, 1.0), (1.0, 0.0)]);
+        dataset.add_points("b".into(), vec![(0.0, f64::NAN)]);
+        let c = dataset.get_cumulatives();
+        assert_eq!(c["a"], Some(vec![(0.0, 0.5), (1.0, 1.0)]));
+        assert!(c["b"].is_none());
+    }
+
     #[test]
     fn hist_empty() {
         let hist = Histogram::default();
@@ -717,5 +2196,20 @@ mod tests {
         assert_eq!(hist.get_value(11.0), Some(0.0));
         assert_eq!(hist.get_value(5.0), Some(0.0));
         assert_eq!(hist.get_value(1.0), Some(1.0));
+        // 4 values out of 6 in a bin of width 1.1011
+        let density = hist.into_density();
+        assert!((density.get_value(0.0).unwrap() - 4.0 / 6.0 / 1.1011).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hist_bins() {
+        let mut hist = Histogram::default();
+        hist.reset_bins(0.0, 10.0, 5);
+        assert_eq!(hist.get_bin(-0.1), None);
+        assert_eq!(hist.get_bin(0.0), Some(0));
+        assert_eq!(hist.get_bin(3.9), Some(1));
+        assert_eq!(hist.get_bin(4.0), Some(2));
+        assert_eq!(hist.get_bin(10.0), Some(4));
+        assert_eq!(hist.get_bin(f64::NAN), None);
     }
 }
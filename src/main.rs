@@ -1,7 +1,18 @@
-use clap::Parser;
-use qdplot::{Canvas, DataSet, PlotKind};
-use std::fs;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use qdplot::{
+    Abscissa, Annotation, Bandwidth, BarMode, Canvas, CanvasError, DataSet, DatasetError,
+    Downsampling, ErrorMapping, Figure, FileWatcher, Fit, Kernel, Key, Layer, Layout, Note,
+    Overplot, PlotKind, QQReference, Shading, SmoothMapping, Span, StatsFormat, StreamedDataSet,
+    StyleMapping, TimeUnit, Viewer, Window, redraw, tail_lines, write_stats,
+};
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, Read};
 use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
 
 /// Tool to quickly plot dataset
 ///
@@ -9,18 +20,463 @@ use std::path::PathBuf;
 #[derive(Parser, Debug)]
 #[command(version, about, long_about)]
 struct Args {
-    /// input CSV file
+    /// input CSV file, - for stdin
     input: PathBuf,
 
     /// Plotkind
     #[arg(short, long, default_value_t=Default::default())]
     kind: PlotKind,
+
+    /// Plot drawn over the others, with ranges fitting all of them:
+    /// kind[:label+label...][@marker], replacing --kind
+    #[arg(long)]
+    layer: Vec<Layer>,
+
+    /// Grid of panels, such as 2x2, each one drawing a kind of --panels
+    #[arg(long)]
+    layout: Option<Layout>,
+
+    /// Plot kinds of the panels of the layout, --kind by default
+    #[arg(long, value_delimiter = ',')]
+    panels: Vec<PlotKind>,
+
+    /// One panel per dataset
+    #[arg(long)]
+    facet_by_series: bool,
+
+    /// Same x and y ranges in all panels
+    #[arg(long)]
+    shared_axes: bool,
+
+    /// Redraw the plot as rows are appended to the input
+    #[arg(long)]
+    follow: bool,
+
+    /// Re-read and redraw the whole plot when the input file is modified,
+    /// as notified by inotify on Linux, its modification time and size
+    /// being polled every refresh period otherwise
+    #[arg(long, conflicts_with = "follow")]
+    watch: bool,
+
+    /// Explore the plot with the keyboard: arrows pan, +/- zoom, k changes the kind,
+    /// s selects a dataset and l hides or shows it, r resets ranges and q quits
+    #[arg(long, conflicts_with_all = ["follow", "watch", "stream", "stats"])]
+    interactive: bool,
+
+    /// Seconds between redraws when following or watching the input
+    #[arg(long, default_value_t = 1.0, value_parser = parse_refresh)]
+    refresh: f64,
+
+    /// Points kept when following the input: last N points, or a span such as 30s, 5m
+    #[arg(long)]
+    window: Option<Window>,
+
+    /// Read the input without keeping the points (histogram, cdf, ccdf and boxplot only)
+    #[arg(long)]
+    stream: bool,
+
+    /// Estimate quantiles of boxplots, CDF and statistics with a sketch above this number of points
+    #[arg(long)]
+    sketch_above: Option<usize>,
+
+    /// Kernel of density estimates
+    #[arg(long, default_value_t=Default::default())]
+    kernel: Kernel,
+
+    /// Bandwidth of density estimates: silverman, scott or a positive number
+    #[arg(long, default_value_t=Default::default())]
+    bandwidth: Bandwidth,
+
+    /// Reference of Q-Q plots: normal, exponential, uniform or a dataset label
+    #[arg(long, default_value_t=Default::default())]
+    qq_reference: QQReference,
+
+    /// Layout of bars of different datasets
+    #[arg(long, default_value_t=Default::default())]
+    bar_mode: BarMode,
+
+    /// Draw bars horizontally
+    #[arg(long)]
+    horizontal: bool,
+
+    /// Consider the first column as unix timestamps in this unit
+    #[arg(long)]
+    epoch: Option<TimeUnit>,
+
+    /// Format of dates on the time axis (%Y, %y, %m, %b, %d, %H, %M, %S)
+    #[arg(long)]
+    time_format: Option<String>,
+
+    /// Fit drawn over each dataset of point plots: linear, polyN, exp or power
+    #[arg(long)]
+    trend: Option<Fit>,
+
+    /// Smoothing of point plots: [label=]sma:N, ema:ALPHA, median:N or loess:SPAN
+    #[arg(long)]
+    smooth: Vec<SmoothMapping>,
+
+    /// Draw raw points along smoothed datasets
+    #[arg(long)]
+    show_raw: bool,
+
+    /// Reduction of large datasets before drawing points
+    #[arg(long, default_value_t=Default::default())]
+    downsample: Downsampling,
+
+    /// Style of a dataset: label=item:item... with a marker character, a color
+    /// (name, 0-255 or #rrggbb), a line style (solid, dashed, dotted) or hidden
+    #[arg(long)]
+    style: Vec<StyleMapping>,
+
+    /// Dotted grid lines at the ticks of the axes, behind the data
+    #[arg(long)]
+    grid: bool,
+
+    /// Datasets of point plots drawn against a secondary y axis, on the right
+    #[arg(long, value_delimiter = ',')]
+    secondary_axis: Vec<String>,
+
+    /// Horizontal reference line at a y value, such as an SLO threshold
+    #[arg(long, allow_negative_numbers = true)]
+    hline: Vec<f64>,
+
+    /// Vertical reference line at an x value or a date, such as a deploy time
+    #[arg(long, allow_negative_numbers = true)]
+    vline: Vec<Abscissa>,
+
+    /// Text written at data coordinates: x,y,text, x being a value or a date
+    #[arg(long, allow_hyphen_values = true)]
+    annotate: Vec<Note>,
+
+    /// Shaded band between two x values or dates: low..high
+    #[arg(long, allow_hyphen_values = true)]
+    x_region: Vec<Span<Abscissa>>,
+
+    /// Shaded band between two y values: low..high
+    #[arg(long, allow_hyphen_values = true)]
+    y_region: Vec<Span>,
+
+    /// Uncertainty of y values: label=error, label=minus/plus or label=low..high
+    #[arg(long)]
+    y_error: Vec<ErrorMapping>,
+
+    /// Uncertainty of x values: label=error, label=minus/plus or label=low..high
+    #[arg(long)]
+    x_error: Vec<ErrorMapping>,
+
+    /// Rendering of cells hit by several points
+    #[arg(long, default_value_t=Default::default())]
+    overplot: Overplot,
+
+    /// Rendering of densities (heatmap, density overplot)
+    #[arg(long, default_value_t=Default::default())]
+    shading: Shading,
+
+    /// Print summary statistics of each dataset instead of plotting
+    #[arg(long)]
+    stats: bool,
+
+    /// Print the plot as an SVG document, uncertainties of y values being shaded bands
+    #[arg(long, conflicts_with_all = [
+        "interactive", "follow", "watch", "stream", "stats", "layout", "facet_by_series",
+    ])]
+    svg: bool,
+
+    /// Format of the summary statistics
+    #[arg(long, default_value_t=Default::default())]
+    stats_format: StatsFormat,
 }
 
 fn main() {
     let args = Args::parse();
-    let dataset = DataSet::from_csv(fs::read_to_string(args.input).unwrap().as_str()).unwrap();
+    if let Some(layout) = args.layout
+        && args.panels.len() > layout.rows * layout.columns
+    {
+        Args::command()
+            .error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "layout {layout} cannot hold the {} panels",
+                    args.panels.len()
+                ),
+            )
+            .exit();
+    }
+    if args.watch && args.input.as_os_str() == "-" {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--watch needs an input file, standard input cannot be re-read",
+            )
+            .exit();
+    }
+    let reader: Box<dyn BufRead + Send> = if args.input.as_os_str() == "-" {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        Box::new(BufReader::new(File::open(&args.input).unwrap()))
+    };
+    if args.stream {
+        let dataset = StreamedDataSet::from_reader(reader).unwrap();
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, args.kind).unwrap();
+        println!("{canvas}");
+        return;
+    }
+    if args.follow {
+        follow(&args, reader);
+        return;
+    }
+    if args.watch {
+        watch(&args);
+        return;
+    }
+    let mut dataset = DataSet::from_reader(reader).unwrap();
+    configure(&mut dataset, &args);
+    if args.stats {
+        write_stats(&mut io::stdout(), &dataset.get_stats(), args.stats_format).unwrap();
+        return;
+    }
+    if args.interactive {
+        interactive(&args, &dataset);
+        return;
+    }
+    if args.facet_by_series || args.layout.is_some() {
+        print!("{}", figure(&dataset, &args));
+        return;
+    }
+    print!("{}", render(&dataset, &args));
+}
+
+/// Seconds between redraws, strictly positive
+fn parse_refresh(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(seconds),
+        _ => Err(format!(
+            "invalid refresh '{s}': expected a positive number of seconds"
+        )),
+    }
+}
+
+/// Apply the plot options
+fn configure(dataset: &mut DataSet, args: &Args) {
+    for mapping in &args.y_error {
+        dataset
+            .set_y_error(&mapping.label, mapping.spec.clone())
+            .unwrap();
+    }
+    for mapping in &args.x_error {
+        dataset
+            .set_x_error(&mapping.label, mapping.spec.clone())
+            .unwrap();
+    }
+    for mapping in &args.style {
+        dataset.set_style(&mapping.label, mapping.style.clone());
+    }
+    dataset.set_secondary_axis(args.secondary_axis.clone());
+    dataset.set_grid(args.grid);
+    let annotations = [
+        args.hline.iter().map(|&y| Annotation::HLine(y)).collect(),
+        args.vline.iter().map(|&x| Annotation::VLine(x)).collect(),
+        args.annotate
+            .iter()
+            .cloned()
+            .map(Annotation::Text)
+            .collect(),
+        args.x_region
+            .iter()
+            .map(|&s| Annotation::XRegion(s))
+            .collect(),
+        args.y_region
+            .iter()
+            .map(|&s| Annotation::YRegion(s))
+            .collect::<Vec<_>>(),
+    ];
+    for annotation in annotations.into_iter().flatten() {
+        dataset.add_annotation(annotation);
+    }
+    dataset.set_kernel(args.kernel);
+    dataset.set_bandwidth(args.bandwidth);
+    dataset.set_qq_reference(args.qq_reference.clone()).unwrap();
+    dataset.set_bar_layout(args.bar_mode, args.horizontal);
+    dataset.set_shading(args.shading);
+    dataset.set_overplot(args.overplot);
+    dataset.set_trend(args.trend);
+    for mapping in &args.smooth {
+        dataset.set_smoothing(mapping.label.as_deref(), mapping.smoothing);
+    }
+    dataset.set_show_raw(args.show_raw);
+    dataset.set_downsampling(args.downsample);
+    dataset.set_sketch_threshold(args.sketch_above);
+    if let Some(unit) = args.epoch {
+        dataset.set_time_axis(unit);
+    }
+    if let Some(format) = &args.time_format {
+        dataset.set_time_format(format.clone());
+    }
+}
+
+/// Plot followed by the fitted trends, if any
+fn render(dataset: &DataSet, args: &Args) -> String {
     let mut canvas = Canvas::new();
-    let _ = dataset.draw_into(&mut canvas, args.kind);
-    println!("{canvas}");
+    let drawn = if args.layer.is_empty() {
+        dataset.draw_into(&mut canvas, args.kind)
+    } else {
+        dataset.draw_layers(&mut canvas, &args.layer)
+    };
+    if let Err(e @ CanvasError::Unsupported(_)) = drawn {
+        eprintln!("{e}");
+    }
+    if args.svg {
+        return canvas.to_svg();
+    }
+    let mut out = format!("{canvas}\n");
+    if let PlotKind::Point = args.kind {
+        for (label, trend) in dataset.get_trends() {
+            out += &format!("{label}: {trend}\n");
+        }
+    }
+    out
+}
+
+/// Panels of the layout, or one panel per dataset
+fn figure(dataset: &DataSet, args: &Args) -> Figure {
+    let mut figure = if args.facet_by_series {
+        Figure::facet_by_series(dataset, args.kind, args.layout)
+    } else {
+        let kinds = if args.panels.is_empty() {
+            vec![args.kind]
+        } else {
+            args.panels.clone()
+        };
+        let mut figure = Figure::new(args.layout.unwrap_or(Layout::fitting(kinds.len())));
+        for kind in kinds {
+            figure
+                .add_panel(kind.to_string(), dataset.clone(), kind)
+                .unwrap();
+        }
+        figure
+    };
+    figure.share_axes(args.shared_axes, args.shared_axes);
+    figure
+}
+
+/// Redraw the plot as rows are appended to the input
+fn follow(args: &Args, reader: Box<dyn BufRead + Send>) {
+    let refresh = Duration::from_secs_f64(args.refresh);
+    // stdin ends when closed, files are polled for new rows
+    let poll = (args.input.as_os_str() != "-").then_some(refresh / 4);
+    let lines = tail_lines(reader, poll);
+    let Ok(header) = lines.recv() else {
+        return;
+    };
+    let mut dataset = DataSet::from_csv(&header).unwrap();
+    configure(&mut dataset, args);
+    let mut stdout = io::stdout();
+    let mut drawn = 0;
+    // rows may be malformed or partially written, they are skipped
+    let mut skipped = 0;
+    let mut last_error = None;
+    loop {
+        let deadline = Instant::now() + refresh;
+        let mut closed = false;
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            match lines.recv_timeout(timeout) {
+                Ok(line) => {
+                    if let Err(e) = dataset.append_csv_line(&line) {
+                        skipped += 1;
+                        last_error = Some(format!("{e} in '{line}'"));
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    closed = true;
+                    break;
+                }
+            }
+        }
+        if let Some(window) = args.window {
+            dataset.apply_window(window);
+        }
+        let mut frame = render(&dataset, args);
+        if let Some(error) = &last_error {
+            frame += &format!("{skipped} rows skipped, last one: {error}\n");
+        }
+        drawn = redraw(&mut stdout, drawn, &frame).unwrap();
+        if closed {
+            return;
+        }
+    }
+}
+
+/// Redraw the whole plot when the input file is modified
+fn watch(args: &Args) {
+    let refresh = Duration::from_secs_f64(args.refresh);
+    let mut watcher = FileWatcher::new(&args.input);
+    let mut stdout = io::stdout();
+    let mut drawn = 0;
+    loop {
+        if watcher.changed().unwrap() {
+            let dataset = File::open(&args.input)
+                .map_err(DatasetError::from)
+                .and_then(|file| DataSet::from_reader(BufReader::new(file)));
+            match dataset {
+                Ok(mut dataset) => {
+                    configure(&mut dataset, args);
+                    drawn = redraw(&mut stdout, drawn, &render(&dataset, args)).unwrap();
+                }
+                // the file may be in the middle of being written, or replaced
+                Err(_) => watcher.reset(),
+            }
+        }
+        watcher.wait(refresh);
+    }
+}
+
+/// Redraw the plot after each key press, the terminal being in raw mode
+fn interactive(args: &Args, dataset: &DataSet) {
+    let mut tty = File::open("/dev/tty").unwrap();
+    let _raw = RawMode::enable().unwrap();
+    let mut viewer = Viewer::new(args.kind);
+    let mut stdout = io::stdout();
+    let mut drawn = 0;
+    let mut input = [0; 32];
+    loop {
+        // newlines are not translated in raw mode
+        let frame = viewer.draw(dataset).replace('\n', "\r\n");
+        drawn = redraw(&mut stdout, drawn, &frame).unwrap();
+        let n = tty.read(&mut input).unwrap_or(0);
+        let keys = Key::parse_all(&input[..n]);
+        if n == 0 || !keys.into_iter().all(|key| viewer.handle(key)) {
+            break;
+        }
+    }
+}
+
+/// Terminal in raw mode, its previous settings being restored when dropped,
+/// even when panicking
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        Ok(Self { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[self.saved.trim()]);
+    }
+}
+
+/// Run stty on the terminal, returning its output
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(File::open("/dev/tty")?)
+        .output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
@@ -0,0 +1,166 @@
+use crate::{Canvas, CanvasError, Color, Shading, get_cell};
+use clap::ValueEnum;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// Marker of cells hit by several datasets
+pub const COLLISION: char = '&';
+
+/// How cells hit by several points are rendered
+#[derive(Debug, Default, Copy, Clone, PartialEq, ValueEnum)]
+pub enum Overplot {
+    /// The last drawn point wins
+    #[default]
+    Overwrite,
+
+    /// Characters (or colors) of increasing density, whatever the dataset
+    Density,
+
+    /// Dataset marker, or a special marker when several datasets share the cell
+    Collision,
+}
+
+impl Display for Overplot {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Overplot::Overwrite => write!(f, "overwrite"),
+            Overplot::Density => write!(f, "density"),
+            Overplot::Collision => write!(f, "collision"),
+        }
+    }
+}
+
+/// Points hitting a cell
+#[derive(Debug, Default, Copy, Clone)]
+struct Cell {
+    count: usize,
+    /// Index, symbol and color of the last dataset
+    last: Option<(usize, char, Option<Color>)>,
+    /// Whether several datasets hit the cell
+    collision: bool,
+}
+
+/// Points hitting each cell of a canvas
+#[derive(Debug, Default)]
+pub struct Hits {
+    /// Vec<line: Vec<Cell>>
+    cells: Vec<Vec<Cell>>,
+}
+
+impl Hits {
+    pub fn new(canvas: &Canvas) -> Self {
+        Self {
+            cells: vec![vec![Cell::default(); canvas.width]; canvas.height],
+        }
+    }
+
+    /// Record a point of the dataset of index series, represented by symbole
+    /// and drawn with the pen of the canvas
+    ///
+    /// Points outside of the canvas and NaN are ignored
+    pub fn add(&mut self, canvas: &Canvas, x: f64, y: f64, series: usize, symbole: char) {
+        if x.is_nan() || y.is_nan() {
+            return;
+        }
+        let (Ok(line), Ok(column)) = (
+            canvas.get_line(y),
+            get_cell(x, canvas.x_range.0, canvas.x_range.1, canvas.width),
+        ) else {
+            return;
+        };
+        if let Some(cell) = self.cells.get_mut(line).and_then(|l| l.get_mut(column)) {
+            cell.count += 1;
+            cell.collision |= cell.last.is_some_and(|(s, _, _)| s != series);
+            cell.last = Some((series, symbole, canvas.pen));
+        }
+    }
+
+    /// Number of points in a cell
+    pub fn count(&self, line: usize, column: usize) -> usize {
+        self.cells[line][column].count
+    }
+
+    /// Whether several datasets have points in a cell
+    pub fn collides(&self, line: usize, column: usize) -> bool {
+        self.cells[line][column].collision
+    }
+
+    pub fn draw_into(
+        &self,
+        canvas: &mut Canvas,
+        overplot: Overplot,
+        shading: Shading,
+    ) -> Result<(), CanvasError> {
+        let max = self
+            .cells
+            .iter()
+            .flatten()
+            .map(|c| c.count)
+            .max()
+            .unwrap_or(0);
+        for (line, cells) in self.cells.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                let Some((_, symbole, color)) = cell.last else {
+                    continue;
+                };
+                // only cells showing the marker of a dataset have its color
+                canvas.set_pen(None);
+                match overplot {
+                    Overplot::Collision if cell.collision => {
+                        canvas.set_cell(line, column, COLLISION)?
+                    }
+                    Overplot::Overwrite | Overplot::Collision => {
+                        canvas.set_pen(color);
+                        canvas.set_cell(line, column, symbole)?
+                    }
+                    Overplot::Density => {
+                        let count = cell.count;
+                        match shading.color(count as f64 / max as f64) {
+                            Some(color) => canvas.set_background(line, column, color)?,
+                            None => canvas.set_cell(line, column, Shading::symbole(count, max))?,
+                        }
+                    }
+                }
+            }
+        }
+        canvas.set_pen(None);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hits() {
+        let mut canvas = Canvas::from_size(10, 10);
+        canvas.set_x_range(0.0, 1.0);
+        canvas.set_y_range(0.0, 1.0);
+        let mut hits = Hits::new(&canvas);
+        for _ in 0..4 {
+            hits.add(&canvas, 0.0, 0.0, 0, 'a');
+        }
+        hits.add(&canvas, 1.0, 1.0, 0, 'a');
+        hits.add(&canvas, 1.0, 1.0, 1, 'b');
+        // datasets sharing a marker
+        hits.add(&canvas, 0.0, 1.0, 0, 'a');
+        hits.add(&canvas, 0.0, 1.0, 2, 'a');
+        let (line, column) = (canvas.get_line(0.0).unwrap(), 1);
+        assert_eq!(hits.count(line, column), 4);
+        assert!(!hits.collides(line, column));
+
+        hits.draw_into(&mut canvas, Overplot::Collision, Shading::Ascii)
+            .unwrap();
+        let text = canvas.to_string();
+        assert_eq!(text.matches('&').count(), 2);
+        assert_eq!(text.matches('a').count(), 1);
+
+        canvas.clear();
+        hits.draw_into(&mut canvas, Overplot::Density, Shading::Ascii)
+            .unwrap();
+        let text = canvas.to_string();
+        assert_eq!(text.matches('@').count(), 1);
+        assert_eq!(text.matches('+').count(), 2);
+    }
+}
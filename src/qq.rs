@@ -0,0 +1,216 @@
+use crate::{Canvas, CanvasError, get_mean_std_dev, get_quantile};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Distribution the series are compared to
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum QQReference {
+    /// Normal distribution with the mean and standard deviation of the series
+    #[default]
+    Normal,
+
+    /// Exponential distribution with the mean of the series
+    Exponential,
+
+    /// Uniform distribution between the extrema of the series
+    Uniform,
+
+    /// Empirical distribution of another series
+    Series(String),
+}
+
+impl Display for QQReference {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            QQReference::Normal => write!(f, "normal"),
+            QQReference::Exponential => write!(f, "exponential"),
+            QQReference::Uniform => write!(f, "uniform"),
+            QQReference::Series(label) => write!(f, "{label}"),
+        }
+    }
+}
+
+impl FromStr for QQReference {
+    type Err = String;
+
+    /// Anything else than a distribution name is a series label
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err("empty reference".into()),
+            "normal" => Ok(QQReference::Normal),
+            "exponential" => Ok(QQReference::Exponential),
+            "uniform" => Ok(QQReference::Uniform),
+            label => Ok(QQReference::Series(label.into())),
+        }
+    }
+}
+
+/// Quantile-quantile plot of a series against a reference distribution
+#[derive(Debug, Default)]
+pub struct QQ {
+    /// (reference quantile, sample quantile)
+    points: Vec<(f64, f64)>,
+}
+
+impl QQ {
+    /// `reference` holds the values of the reference series, if any
+    pub fn from_vec(input: Vec<f64>, kind: &QQReference, reference: Option<&[f64]>) -> Self {
+        let mut x: Vec<f64> = input.into_iter().filter(|a| !a.is_nan()).collect();
+        if x.is_empty() {
+            return Self::default();
+        }
+        x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = x.len() as f64;
+        let (mean, std_dev) = get_mean_std_dev(x.iter().copied());
+        let (min, max) = (x[0], x[x.len() - 1]);
+        let mut reference: Vec<f64> = reference
+            .unwrap_or_default()
+            .iter()
+            .filter(|a| !a.is_nan())
+            .copied()
+            .collect();
+        reference.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let theoretical = |i: usize| {
+            // plotting position of the sample
+            let p = (i as f64 + 0.5) / n;
+            match kind {
+                QQReference::Normal => mean + std_dev * normal_quantile(p),
+                QQReference::Exponential => -mean * (1.0 - p).ln(),
+                QQReference::Uniform => min + (max - min) * p,
+                QQReference::Series(_) if reference.is_empty() => f64::NAN,
+                // empirical quantile of the sample, read on the reference the same
+                // way, so that a series compared with itself lies on the diagonal
+                QQReference::Series(_) => {
+                    let q = if x.len() > 1 {
+                        i as f64 / (n - 1.0)
+                    } else {
+                        0.5
+                    };
+                    get_quantile(&reference, q)
+                }
+            }
+        };
+        let points = x
+            .iter()
+            .enumerate()
+            .map(|(i, &y)| (theoretical(i), y))
+            .filter(|p| !p.0.is_nan())
+            .collect();
+        Self { points }
+    }
+
+    /// (reference quantile, sample quantile) pairs
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    pub fn draw_into(&self, canvas: &mut Canvas, symbole: char) -> Result<(), CanvasError> {
+        for &(x, y) in &self.points {
+            // points out of the canvas, e.g. when zooming, are skipped
+            let _ = canvas.draw_value(x, y, symbole);
+        }
+        Ok(())
+    }
+}
+
+/// Quantile function of the standard normal distribution
+///
+/// Rational approximation from Peter J. Acklam, relative error below 1.15e-9
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.383_577_518_672_69e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_quantiles() {
+        assert_eq!(normal_quantile(0.5), 0.0);
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+        assert!((normal_quantile(0.01) + 2.326348).abs() < 1e-6);
+    }
+
+    #[test]
+    fn qq_uniform() {
+        let qq = QQ::from_vec(vec![0.0, 4.0, 2.0, 3.0], &QQReference::Uniform, None);
+        assert_eq!(
+            qq.points(),
+            &[(0.5, 0.0), (1.5, 2.0), (2.5, 3.0), (3.5, 4.0)]
+        );
+    }
+
+    #[test]
+    fn qq_series() {
+        let reference = [10.0, 20.0, 30.0, 40.0];
+        let kind: QQReference = "other".parse().unwrap();
+        let qq = QQ::from_vec(vec![1.0, 2.0], &kind, Some(&reference));
+        assert_eq!(qq.points(), &[(10.0, 1.0), (40.0, 2.0)]);
+        let qq = QQ::from_vec(vec![1.0, 2.0, 3.0], &kind, Some(&reference));
+        assert_eq!(qq.points()[1], (25.0, 2.0));
+        // a series compared with itself lies on the diagonal
+        let qq = QQ::from_vec(reference.to_vec(), &kind, Some(&reference));
+        assert!(qq.points().iter().all(|(x, y)| x == y));
+    }
+
+    #[test]
+    fn qq_reference_unknown() {
+        let mut dataset = crate::DataSet::from_csv("x,a,b\n0,1,2\n1,3,5\n").unwrap();
+        assert!(dataset.set_qq_reference("normla".parse().unwrap()).is_err());
+        dataset.set_qq_reference("b".parse().unwrap()).unwrap();
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, crate::PlotKind::QQ).unwrap();
+        assert!(canvas.to_string().contains('a'));
+    }
+}
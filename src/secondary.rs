@@ -0,0 +1,128 @@
+use crate::{Canvas, CanvasError, DataSet, MARGIN};
+
+/// Affine map from secondary y values to primary ones
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct AxisMap {
+    from: (f64, f64),
+    to: (f64, f64),
+}
+
+impl AxisMap {
+    pub(crate) fn apply(&self, y: f64) -> f64 {
+        self.to.0 + (y - self.from.0) * (self.to.1 - self.to.0) / (self.from.1 - self.from.0)
+    }
+}
+
+impl Canvas {
+    /// Range of the secondary y axis, with the margins of the primary one
+    pub(crate) fn set_y2_range(&mut self, y_min: f64, y_max: f64) {
+        assert!(y_min < y_max);
+        let delta = y_max - y_min;
+        let y_range = (y_min - MARGIN * delta, y_max + MARGIN * delta);
+        let cell_width = (y_range.1 - y_range.0) / self.height as f64;
+        self.y2_range = Some((
+            y_min - MARGIN * delta - 2.0 * cell_width,
+            y_max + MARGIN * delta,
+        ));
+    }
+
+    /// Map of the secondary y values onto the canvas, if there is a secondary axis
+    pub(crate) fn secondary_map(&self) -> Option<AxisMap> {
+        self.y2_range.map(|from| AxisMap {
+            from,
+            to: self.y_range,
+        })
+    }
+
+    /// Draw the secondary y axis on the last column, with the values of its
+    /// ticks on its left and its label on the first line
+    pub(crate) fn draw_secondary_axis(&mut self, label: &str) -> Result<(), CanvasError> {
+        let Some((y_min, y_max)) = self.y2_range else {
+            return Ok(());
+        };
+        let column = self.width - 1;
+        let decimals = (2.0 - (y_max - y_min).log10().floor()).clamp(0.0, 6.0) as usize;
+        let step = (y_max - y_min) / (self.height - 1) as f64;
+        for line in 0..self.height {
+            // same ticks as the primary axis, from the bottom of the canvas
+            let tick = (self.height - 1 - line).is_multiple_of(5);
+            self.set_empty_cell(line, column, if tick { '┼' } else { '│' })?;
+            // the first and last lines are left to the label and the x axis
+            if tick && line > 0 && line + 1 < self.height {
+                let value = y_min + (self.height - line) as f64 * step;
+                let text = format!("{value:.decimals$}");
+                if text.len() < column {
+                    self.draw_text(line, column - text.len(), &text)?;
+                }
+            }
+        }
+        let label: String = label.chars().take(column).collect();
+        self.draw_text(0, column - label.chars().count(), &label)
+    }
+}
+
+impl DataSet {
+    /// Draw some datasets against a secondary y axis, on the right of point plots
+    pub fn set_secondary_axis(&mut self, labels: Vec<String>) {
+        self.secondary = labels;
+    }
+
+    pub(crate) fn is_secondary(&self, label: &str) -> bool {
+        self.secondary.iter().any(|l| l == label)
+    }
+
+    /// Copy of the datasets, secondary y values being mapped to primary ones
+    pub(crate) fn map_secondary(&self, map: AxisMap) -> DataSet {
+        let mut mapped = self.clone();
+        for (label, points) in mapped.dataset.iter_mut() {
+            if self.is_secondary(label) {
+                for point in points.iter_mut() {
+                    point.1 = map.apply(point.1);
+                }
+            }
+        }
+        for (label, errors) in mapped.errors.iter_mut() {
+            if self.is_secondary(label) {
+                for error in errors.iter_mut() {
+                    error.y = error.y.map(|(low, high)| (map.apply(low), map.apply(high)));
+                }
+            }
+        }
+        mapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlotKind;
+
+    #[test]
+    fn axis_map() {
+        let map = AxisMap {
+            from: (0.0, 1000.0),
+            to: (-1.0, 1.0),
+        };
+        assert_eq!(map.apply(500.0), 0.0);
+        assert_eq!(map.apply(1000.0), 1.0);
+    }
+
+    #[test]
+    fn secondary_axis() {
+        let mut dataset =
+            DataSet::from_csv("x,latency,throughput\n0,1,2000\n1,2,3000\n2,3,1000\n").unwrap();
+        dataset.set_secondary_axis(vec!["throughput".to_string()]);
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        let text = canvas.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].ends_with("throughput│"));
+        // ticks every 5 lines from the bottom, as on the primary axis
+        assert!(lines[4].starts_with(" ┼") && lines[4].ends_with(" 2730┼"));
+        // both datasets span the height of the canvas
+        for line in [lines[1], lines[23]] {
+            assert!(line.contains('l') && line.contains('t'));
+        }
+        assert!(lines.iter().all(|l| l.ends_with(['│', '┼'])));
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{CDF, Quantiles};
+
+/// Default accuracy parameter of the sketches
+pub const SKETCH_K: usize = 200;
+
+/// Ratio between the capacities of consecutive compactors
+const CAPACITY_RATIO: f64 = 2.0 / 3.0;
+
+/// Mergeable quantile sketch (KLL)
+///
+/// Values are kept in compactors of decreasing capacities. When a compactor
+/// is full, it is sorted and every other value is promoted to the next one,
+/// where values weigh twice as much. Memory grows with k and the logarithm of
+/// the number of values.
+///
+/// Normalized rank error: with the default k = 200, the rank of a returned
+/// quantile is within 1.65% of the requested one with 99% confidence. The
+/// error decreases roughly as 1/k. Extrema are exact.
+#[derive(Debug, Clone)]
+pub struct QuantileSketch {
+    k: usize,
+    /// Values of each level, level h values weighing 2^h
+    compactors: Vec<Vec<f64>>,
+    /// Number of values kept
+    size: usize,
+    /// Number of values kept above which compactors are compacted
+    max_size: usize,
+    count: usize,
+    min: f64,
+    max: f64,
+    /// State of the generator choosing which values are promoted
+    seed: u64,
+}
+
+impl Default for QuantileSketch {
+    fn default() -> Self {
+        Self::new(SKETCH_K)
+    }
+}
+
+impl QuantileSketch {
+    /// Sketch whose accuracy grows with k (at least 8)
+    pub fn new(k: usize) -> Self {
+        let mut sketch = Self {
+            k: k.max(8),
+            compactors: Vec::new(),
+            size: 0,
+            max_size: 0,
+            count: 0,
+            min: f64::NAN,
+            max: f64::NAN,
+            seed: 0x2545_f491_4f6c_dd1d,
+        };
+        sketch.grow();
+        sketch
+    }
+
+    pub fn from_slice(input: &[f64]) -> Self {
+        let mut sketch = Self::default();
+        for &x in input {
+            sketch.add(x);
+        }
+        sketch
+    }
+
+    /// NaN are ignored
+    pub fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.compactors[0].push(value);
+        self.size += 1;
+        if self.size >= self.max_size {
+            self.compress();
+        }
+    }
+
+    /// Add the values summarized by another sketch
+    pub fn merge(&mut self, other: &Self) {
+        while self.compactors.len() < other.compactors.len() {
+            self.grow();
+        }
+        for (compactor, values) in self.compactors.iter_mut().zip(&other.compactors) {
+            compactor.extend(values);
+        }
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.size = self.compactors.iter().map(Vec::len).sum();
+        while self.size >= self.max_size {
+            self.compress();
+        }
+    }
+
+    /// Number of values added
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Approximate fraction of the values lower or equal to x
+    pub fn rank(&self, x: f64) -> f64 {
+        let below: u64 = self
+            .weighted()
+            .iter()
+            .filter(|(v, _)| *v <= x)
+            .map(|(_, w)| w)
+            .sum();
+        below as f64 / self.count.max(1) as f64
+    }
+
+    /// Approximate quantile, None if no value has been added
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let q = q.clamp(0.0, 1.0);
+        if q == 0.0 {
+            return Some(self.min);
+        }
+        if q == 1.0 {
+            return Some(self.max);
+        }
+        let rank = q * self.count as f64;
+        let mut seen = 0;
+        for (value, weight) in self.weighted() {
+            seen += weight;
+            if seen as f64 >= rank {
+                return Some(value);
+            }
+        }
+        Some(self.max)
+    }
+
+    /// Approximate cumulative distribution function
+    pub fn cdf(&self) -> CDF {
+        let mut steps: Vec<(f64, f64)> = Vec::new();
+        let mut seen = 0;
+        for (value, weight) in self.weighted() {
+            seen += weight;
+            let cur = seen as f64 / self.count as f64;
+            match steps.last_mut() {
+                Some(point) if point.0 == value => point.1 = cur,
+                _ => steps.push((value, cur)),
+            }
+        }
+        CDF {
+            steps,
+            complementary: false,
+        }
+    }
+
+    /// Approximate quartiles, whiskers being clamped to the extrema
+    ///
+    /// Outliers are not kept by the sketch
+    pub fn quantiles(&self) -> Option<Quantiles> {
+        let [q1, q2, q3] = [0.25, 0.5, 0.75].map(|q| self.quantile(q));
+        let (q1, q2, q3) = (q1?, q2?, q3?);
+        let inter_quartiles = 1.5 * (q3 - q1);
+        Some(Quantiles {
+            min: self.min.max(q2 - inter_quartiles),
+            q1,
+            q2,
+            q3,
+            max: self.max.min(q2 + inter_quartiles),
+            outliers: Vec::new(),
+        })
+    }
+
+    /// (value, weight) of the values kept, sorted by value
+    fn weighted(&self) -> Vec<(f64, u64)> {
+        let mut weighted: Vec<(f64, u64)> = self
+            .compactors
+            .iter()
+            .enumerate()
+            .flat_map(|(h, c)| c.iter().map(move |&v| (v, 1 << h)))
+            .collect();
+        weighted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        weighted
+    }
+
+    fn capacity(&self, level: usize) -> usize {
+        let depth = self.compactors.len() - level - 1;
+        (CAPACITY_RATIO.powi(depth as i32) * self.k as f64).ceil() as usize + 1
+    }
+
+    fn grow(&mut self) {
+        self.compactors.push(Vec::new());
+        self.max_size = (0..self.compactors.len()).map(|h| self.capacity(h)).sum();
+    }
+
+    /// Compact full levels, from the lowest one, until the sketch is small enough
+    fn compress(&mut self) {
+        for h in 0..self.compactors.len() {
+            if self.compactors[h].len() < self.capacity(h) {
+                continue;
+            }
+            if h + 1 == self.compactors.len() {
+                self.grow();
+            }
+            let mut values = std::mem::take(&mut self.compactors[h]);
+            values.sort_by(f64::total_cmp);
+            // an odd value out stays at its level
+            if values.len() % 2 == 1 {
+                self.compactors[h].push(values.pop().expect("odd length"));
+            }
+            let offset = self.coin();
+            let promoted = values.into_iter().skip(offset).step_by(2);
+            self.compactors[h + 1].extend(promoted);
+            self.size = self.compactors.iter().map(Vec::len).sum();
+            if self.size < self.max_size {
+                break;
+            }
+        }
+    }
+
+    /// Pseudo random 0 or 1 (xorshift), so that results are reproducible
+    fn coin(&mut self) -> usize {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed & 1) as usize
+    }
+}
+
+impl Quantiles {
+    /// Quartiles estimated with a sketch of the input
+    ///
+    /// Whiskers and outliers are exact, the input being scanned once more,
+    /// with the same bounds as [Quantiles::from_slice]
+    pub fn from_sketch(input: &[f64], sketch: &QuantileSketch) -> Option<Self> {
+        let mut q = sketch.quantiles()?;
+        let inter_quartiles = 1.5 * (q.q3 - q.q1);
+        let (lower, upper) = (q.q2 - inter_quartiles, q.q2 + inter_quartiles);
+        let inside = input.iter().copied().filter(|&a| a >= lower && a <= upper);
+        q.min = inside.clone().fold(f64::NAN, f64::min);
+        q.max = inside.fold(f64::NAN, f64::max);
+        q.outliers = input
+            .iter()
+            .copied()
+            .filter(|&a| a < lower || a > upper)
+            .collect();
+        Some(q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_quantile;
+
+    /// Values spread over several orders of magnitude, in a scrambled order
+    fn values(n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| (i * 7919 % n) as f64)
+            .map(|x| (x / 1000.0).exp())
+            .collect()
+    }
+
+    #[test]
+    fn sketch_against_exact() {
+        let input = values(100_000);
+        let sketch = QuantileSketch::from_slice(&input);
+        assert_eq!(sketch.count(), input.len());
+        let mut sorted = input.clone();
+        sorted.sort_by(f64::total_cmp);
+        for q in [0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99] {
+            let approx = sketch.quantile(q).unwrap();
+            // error is measured on ranks
+            let exact_rank = sorted.partition_point(|&x| x <= approx) as f64 / input.len() as f64;
+            assert!((exact_rank - q).abs() < 0.0165, "q={q}: rank {exact_rank}");
+            assert!((sketch.rank(get_quantile(&sorted, q)) - q).abs() < 0.0165);
+        }
+        assert_eq!(sketch.quantile(0.0), Some(sorted[0]));
+        assert_eq!(sketch.quantile(1.0), Some(sorted[sorted.len() - 1]));
+        assert!(sketch.size < 1000);
+    }
+
+    #[test]
+    fn sketch_merge() {
+        let input = values(50_000);
+        let (left, right) = input.split_at(20_000);
+        let mut sketch = QuantileSketch::from_slice(left);
+        sketch.merge(&QuantileSketch::from_slice(right));
+        assert_eq!(sketch.count(), input.len());
+        let whole = QuantileSketch::from_slice(&input);
+        for q in [0.1, 0.5, 0.9] {
+            assert!((sketch.rank(whole.quantile(q).unwrap()) - q).abs() < 0.0165);
+        }
+    }
+
+    #[test]
+    fn sketch_small() {
+        let sketch = QuantileSketch::from_slice(&[3.0, 1.0, f64::NAN, 2.0]);
+        assert_eq!(sketch.quantile(0.5), Some(2.0));
+        assert_eq!(
+            sketch.cdf().steps(),
+            [(1.0, 1.0 / 3.0), (2.0, 2.0 / 3.0), (3.0, 1.0)]
+        );
+        assert_eq!(QuantileSketch::default().quantile(0.5), None);
+
+        let input = [1.0, 2.0, 3.0, 4.0, 100.0];
+        let q = Quantiles::from_sketch(&input, &QuantileSketch::from_slice(&input)).unwrap();
+        assert_eq!((q.min(), q.max(), q.outliers()), (1.0, 4.0, &[100.0][..]));
+    }
+
+    #[test]
+    fn sketch_constant() {
+        for input in [
+            vec![5.0; 1000],
+            vec![5.0],
+            [vec![5.0; 9], vec![9.0]].concat(),
+        ] {
+            let sketch = QuantileSketch::from_slice(&input);
+            let q = Quantiles::from_sketch(&input, &sketch).unwrap();
+            assert_eq!(q, Quantiles::from_slice(&input));
+        }
+    }
+}
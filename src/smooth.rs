@@ -0,0 +1,230 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Transform reducing the noise of a series
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Smoothing {
+    /// Mean of a window of points, centered on each point
+    Sma(usize),
+
+    /// Exponential moving average, with a specific smoothing factor in ]0, 1]
+    Ema(f64),
+
+    /// Median of a window of points, centered on each point
+    Median(usize),
+
+    /// Locally weighted linear regression, on a specific fraction of the points
+    Loess(f64),
+}
+
+impl Display for Smoothing {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Smoothing::Sma(window) => write!(f, "sma:{window}"),
+            Smoothing::Ema(alpha) => write!(f, "ema:{alpha}"),
+            Smoothing::Median(window) => write!(f, "median:{window}"),
+            Smoothing::Loess(span) => write!(f, "loess:{span}"),
+        }
+    }
+}
+
+impl FromStr for Smoothing {
+    type Err = String;
+
+    /// `sma:N`, `ema:ALPHA`, `median:N` or `loess:SPAN`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!("invalid smoothing '{s}': expected sma:N, ema:ALPHA, median:N or loess:SPAN")
+        };
+        let (name, parameter) = s.split_once(':').ok_or_else(invalid)?;
+        let window = || parameter.parse().ok().filter(|&w: &usize| w > 0);
+        let fraction = || {
+            parameter
+                .parse()
+                .ok()
+                .filter(|&f: &f64| f > 0.0 && f <= 1.0)
+        };
+        match name {
+            "sma" => window().map(Smoothing::Sma),
+            "ema" => fraction().map(Smoothing::Ema),
+            "median" => window().map(Smoothing::Median),
+            "loess" => fraction().map(Smoothing::Loess),
+            _ => None,
+        }
+        .ok_or_else(invalid)
+    }
+}
+
+impl Smoothing {
+    /// Smoothed series, sorted by x
+    ///
+    /// NaN are ignored
+    pub fn apply(&self, points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+        let mut points: Vec<(f64, f64)> = points
+            .iter()
+            .copied()
+            .filter(|(x, y)| !x.is_nan() && !y.is_nan())
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let n = points.len();
+        // bounds of the window of a specific size centered on a point,
+        // truncated at the ends of the series
+        let centered = |i: usize, window: usize| {
+            let before = (window - 1) / 2;
+            (i.saturating_sub(before), (i + window - before).min(n))
+        };
+        match *self {
+            Smoothing::Sma(window) => (0..n)
+                .map(|i| {
+                    let (start, end) = centered(i, window);
+                    let sum: f64 = points[start..end].iter().map(|p| p.1).sum();
+                    (points[i].0, sum / (end - start) as f64)
+                })
+                .collect(),
+            Smoothing::Ema(alpha) => {
+                let mut average = None;
+                points
+                    .iter()
+                    .map(|&(x, y)| {
+                        let a = average.map_or(y, |a: f64| alpha * y + (1.0 - alpha) * a);
+                        average = Some(a);
+                        (x, a)
+                    })
+                    .collect()
+            }
+            Smoothing::Median(window) => (0..n)
+                .map(|i| {
+                    let (start, end) = centered(i, window);
+                    let mut values: Vec<f64> = points[start..end].iter().map(|p| p.1).collect();
+                    values.sort_by(f64::total_cmp);
+                    let m = values.len() / 2;
+                    let median = if values.len().is_multiple_of(2) {
+                        (values[m - 1] + values[m]) / 2.0
+                    } else {
+                        values[m]
+                    };
+                    (points[i].0, median)
+                })
+                .collect(),
+            Smoothing::Loess(span) => loess(&points, span),
+        }
+    }
+}
+
+/// Smoothing of a specific dataset, or of all datasets
+///
+/// Written `label=method:parameter` or `method:parameter`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmoothMapping {
+    pub label: Option<String>,
+    pub smoothing: Smoothing,
+}
+
+impl FromStr for SmoothMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some(("", _)) => Err(format!("invalid smoothing '{s}': empty label")),
+            Some((label, smoothing)) => Ok(Self {
+                label: Some(label.into()),
+                smoothing: smoothing.parse()?,
+            }),
+            None => Ok(Self {
+                label: None,
+                smoothing: s.parse()?,
+            }),
+        }
+    }
+}
+
+/// Locally weighted linear regression with tricube weights, on sorted points
+fn loess(points: &[(f64, f64)], span: f64) -> Vec<(f64, f64)> {
+    let n = points.len();
+    let q = ((span * n as f64).ceil() as usize).clamp(2.min(n), n);
+    let mut start = 0;
+    points
+        .iter()
+        .map(|&(x, y)| {
+            // the nearest neighbours of sorted points are contiguous
+            while start + q < n && x - points[start].0 > points[start + q].0 - x {
+                start += 1;
+            }
+            let window = &points[start..start + q];
+            let radius = window.iter().map(|p| (p.0 - x).abs()).fold(0.0, f64::max);
+            if radius == 0.0 {
+                return (x, y);
+            }
+            let weights: Vec<f64> = window
+                .iter()
+                .map(|p| (1.0 - ((p.0 - x).abs() / (radius * 1.000_001)).powi(3)).powi(3))
+                .collect();
+            let total: f64 = weights.iter().sum();
+            let weighted_mean = |f: &dyn Fn(&(f64, f64)) -> f64| -> f64 {
+                window
+                    .iter()
+                    .zip(&weights)
+                    .map(|(p, w)| w * f(p))
+                    .sum::<f64>()
+                    / total
+            };
+            let (mx, my) = (weighted_mean(&|p| p.0), weighted_mean(&|p| p.1));
+            let variance = weighted_mean(&|p| (p.0 - mx).powi(2));
+            if variance == 0.0 {
+                return (x, my);
+            }
+            let slope = weighted_mean(&|p| (p.0 - mx) * (p.1 - my)) / variance;
+            (x, my + slope * (x - mx))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smoothing_parse() {
+        assert_eq!("sma:3".parse(), Ok(Smoothing::Sma(3)));
+        assert_eq!("loess:0.5".parse(), Ok(Smoothing::Loess(0.5)));
+        assert!("ema:2".parse::<Smoothing>().is_err());
+        assert!("median:0".parse::<Smoothing>().is_err());
+        assert!("sma".parse::<Smoothing>().is_err());
+        let m: SmoothMapping = "latency=ema:0.2".parse().unwrap();
+        assert_eq!(m.label.as_deref(), Some("latency"));
+        assert_eq!(m.smoothing, Smoothing::Ema(0.2));
+        assert!("=ema:0.2".parse::<SmoothMapping>().is_err());
+    }
+
+    #[test]
+    fn moving_averages() {
+        let points = [(2.0, 4.0), (0.0, 0.0), (1.0, 2.0), (3.0, f64::NAN)];
+        assert_eq!(
+            Smoothing::Sma(3).apply(&points),
+            [(0.0, 1.0), (1.0, 2.0), (2.0, 3.0)]
+        );
+        assert_eq!(
+            Smoothing::Ema(0.5).apply(&points),
+            [(0.0, 0.0), (1.0, 1.0), (2.0, 2.5)]
+        );
+    }
+
+    #[test]
+    fn rolling_median() {
+        let points = [(0.0, 1.0), (1.0, 100.0), (2.0, 3.0), (3.0, 4.0)];
+        assert_eq!(
+            Smoothing::Median(3).apply(&points),
+            [(0.0, 50.5), (1.0, 3.0), (2.0, 4.0), (3.0, 3.5)]
+        );
+    }
+
+    #[test]
+    fn loess_line() {
+        // a linear series is left unchanged
+        let points: Vec<(f64, f64)> = (0..10).map(|x| (x as f64, 2.0 * x as f64 + 1.0)).collect();
+        for (a, b) in Smoothing::Loess(0.4).apply(&points).iter().zip(&points) {
+            assert!((a.1 - b.1).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{QuantileSketch, Quantiles, get_mean_std_dev, get_quantile};
+use clap::ValueEnum;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::Write;
+
+/// Percentiles reported in the summary
+pub const PERCENTILES: [f64; 6] = [0.01, 0.05, 0.5, 0.9, 0.99, 0.999];
+const PERCENTILE_NAMES: [&str; PERCENTILES.len()] = ["p1", "p5", "p50", "p90", "p99", "p99.9"];
+
+/// Summary statistics of a series
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// Number of valid (non NaN) values
+    pub count: usize,
+    /// Number of NaN values
+    pub nan_count: usize,
+    pub mean: f64,
+    /// Sample standard deviation
+    pub std_dev: f64,
+    pub min: f64,
+    /// Values at each of the [PERCENTILES]
+    pub percentiles: [f64; PERCENTILES.len()],
+    pub max: f64,
+    /// Number of values outside of the boxplot whiskers
+    pub outliers: usize,
+}
+
+impl Stats {
+    pub fn from_slice(input: &[f64]) -> Self {
+        let mut x: Vec<f64> = input.iter().filter(|a| !a.is_nan()).copied().collect();
+        let count = x.len();
+        let nan_count = input.len() - count;
+        if x.is_empty() {
+            return Self {
+                count,
+                nan_count,
+                mean: f64::NAN,
+                std_dev: f64::NAN,
+                min: f64::NAN,
+                percentiles: [f64::NAN; PERCENTILES.len()],
+                max: f64::NAN,
+                outliers: 0,
+            };
+        }
+        x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let (mean, std_dev) = get_mean_std_dev(x.iter().copied());
+        Self {
+            count,
+            nan_count,
+            mean,
+            std_dev,
+            min: x[0],
+            percentiles: PERCENTILES.map(|q| get_quantile(&x, q)),
+            max: x[count - 1],
+            outliers: Quantiles::from_slice(&x).outliers.len(),
+        }
+    }
+
+    /// Statistics whose percentiles are estimated with a [QuantileSketch],
+    /// without copying nor sorting the input
+    pub fn approximate(input: &[f64]) -> Self {
+        let sketch = QuantileSketch::from_slice(input);
+        let count = sketch.count();
+        let Some(quantiles) = Quantiles::from_sketch(input, &sketch) else {
+            return Self::from_slice(input);
+        };
+        let (mean, std_dev) = get_mean_std_dev(input.iter().copied());
+        Self {
+            count,
+            nan_count: input.len() - count,
+            mean,
+            std_dev,
+            min: sketch.quantile(0.0).expect("sketch is not empty"),
+            percentiles: PERCENTILES.map(|q| sketch.quantile(q).expect("sketch is not empty")),
+            max: sketch.quantile(1.0).expect("sketch is not empty"),
+            outliers: quantiles.outliers.len(),
+        }
+    }
+}
+
+/// Output format of the summary statistics
+#[derive(Debug, Default, Copy, Clone, ValueEnum)]
+pub enum StatsFormat {
+    /// Aligned plain text table
+    #[default]
+    Text,
+
+    /// Comma separated values
+    Csv,
+
+    /// JSON object indexed by series label
+    Json,
+}
+
+impl Display for StatsFormat {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            StatsFormat::Text => write!(f, "text"),
+            StatsFormat::Csv => write!(f, "csv"),
+            StatsFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Column names of the report, label excluded
+fn headers() -> Vec<String> {
+    let mut h: Vec<String> = ["count", "nan", "mean", "std_dev", "min"]
+        .map(String::from)
+        .into();
+    h.extend(PERCENTILE_NAMES.map(String::from));
+    h.extend(["max", "outliers"].map(String::from));
+    h
+}
+
+/// Values of the report, in the same order as the headers
+fn values(s: &Stats, float: impl Fn(f64) -> String) -> Vec<String> {
+    let mut v = vec![
+        s.count.to_string(),
+        s.nan_count.to_string(),
+        float(s.mean),
+        float(s.std_dev),
+        float(s.min),
+    ];
+    v.extend(s.percentiles.iter().map(|&p| float(p)));
+    v.push(float(s.max));
+    v.push(s.outliers.to_string());
+    v
+}
+
+/// Write a per series statistics report
+pub fn write_stats(
+    w: &mut impl Write,
+    stats: &[(String, Stats)],
+    format: StatsFormat,
+) -> io::Result<()> {
+    match format {
+        StatsFormat::Text => {
+            let mut rows = vec![[vec![String::new()], headers()].concat()];
+            for (label, s) in stats {
+                rows.push([vec![label.clone()], values(s, |x| format!("{x:.3}"))].concat());
+            }
+            let widths: Vec<usize> = (0..rows[0].len())
+                .map(|c| rows.iter().map(|r| r[c].chars().count()).max().unwrap())
+                .collect();
+            for row in rows {
+                let line: Vec<String> = zip_widths(&row, &widths);
+                writeln!(w, "{}", line.join("  ").trim_end())?;
+            }
+        }
+        StatsFormat::Csv => {
+            writeln!(w, "label,{}", headers().join(","))?;
+            for (label, s) in stats {
+                writeln!(
+                    w,
+                    "{},{}",
+                    csv_escape(label),
+                    values(s, |x| x.to_string()).join(",")
+                )?;
+            }
+        }
+        StatsFormat::Json => {
+            let headers = headers();
+            let series: Vec<String> = stats
+                .iter()
+                .map(|(label, s)| {
+                    let fields: Vec<String> = headers
+                        .iter()
+                        .zip(values(s, json_number))
+                        .map(|(h, v)| format!("\"{h}\": {v}"))
+                        .collect();
+                    format!("  {}: {{{}}}", json_string(label), fields.join(", "))
+                })
+                .collect();
+            writeln!(w, "{{\n{}\n}}", series.join(",\n"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Left align the label column and right align the numbers
+fn zip_widths(row: &[String], widths: &[usize]) -> Vec<String> {
+    row.iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(i, (cell, &w))| {
+            if i == 0 {
+                format!("{cell:<w$}")
+            } else {
+                format!("{cell:>w$}")
+            }
+        })
+        .collect()
+}
+
+fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// JSON has no representation for NaN and infinities
+fn json_number(x: f64) -> String {
+    if x.is_finite() {
+        x.to_string()
+    } else {
+        "null".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_values() {
+        let v = [1.0, 3.0, f64::NAN, 4.0, 0.0, 2.0];
+        let s = Stats::from_slice(&v);
+        assert_eq!(s.count, 5);
+        assert_eq!(s.nan_count, 1);
+        assert_eq!(s.mean, 2.0);
+        assert_eq!(s.std_dev, 2.5f64.sqrt());
+        assert_eq!(s.min, 0.0);
+        assert_eq!(s.max, 4.0);
+        assert_eq!(s.percentiles[1], 0.2);
+        assert_eq!(s.percentiles[2], 2.0);
+        assert_eq!(s.percentiles[5], 3.996);
+        assert_eq!(s.outliers, 0);
+
+        let s = Stats::from_slice(&[1.0, 2.0]);
+        assert_eq!(s.percentiles[2], 1.5);
+        assert_eq!(s.outliers, 0);
+    }
+
+    #[test]
+    fn stats_constant() {
+        for v in [&[5.0, 5.0, 5.0][..], &[5.0]] {
+            let s = Stats::from_slice(v);
+            assert_eq!((s.min, s.max, s.std_dev), (5.0, 5.0, 0.0));
+            assert_eq!(s.percentiles, [5.0; PERCENTILES.len()]);
+            assert_eq!(s.outliers, 0);
+        }
+    }
+
+    #[test]
+    fn stats_approximate() {
+        let v: Vec<f64> = (0..10_000).map(|i| (i * 37 % 10_000) as f64).collect();
+        let (exact, approx) = (Stats::from_slice(&v), Stats::approximate(&v));
+        assert_eq!(
+            (approx.count, approx.mean, approx.std_dev),
+            (exact.count, exact.mean, exact.std_dev)
+        );
+        assert_eq!((approx.min, approx.max), (exact.min, exact.max));
+        for (a, e) in approx.percentiles.iter().zip(exact.percentiles) {
+            // 1.65% of the ranks, values being spread uniformly
+            assert!((a - e).abs() < 165.0, "{a} != {e}");
+        }
+        assert_eq!(Stats::approximate(&[f64::NAN]).nan_count, 1);
+    }
+
+    #[test]
+    fn stats_empty() {
+        let s = Stats::from_slice(&[f64::NAN]);
+        assert_eq!(s.count, 0);
+        assert_eq!(s.nan_count, 1);
+        assert!(s.mean.is_nan());
+    }
+
+    #[test]
+    fn stats_json() {
+        let stats = vec![("a\"b".to_string(), Stats::from_slice(&[f64::NAN]))];
+        let mut out = Vec::new();
+        write_stats(&mut out, &stats, StatsFormat::Json).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("{\n  \"a\\\"b\": {\"count\": 0, \"nan\": 1, \"mean\": null"));
+    }
+}
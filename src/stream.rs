@@ -0,0 +1,363 @@
+use crate::{
+    CDF, Canvas, CanvasError, Color, DatasetError, Histogram, PlotKind, QuantileSketch, Quantiles,
+    draw_cdfs, draw_histograms,
+};
+use std::io::{BufRead, Lines};
+
+/// Number of bins of the histogram kept by an accumulator
+const FINE_BINS: usize = 1024;
+
+/// Rows of a CSV document, read line by line
+///
+/// Each row is made of the first column, unparsed, and the values of the
+/// other columns
+pub struct CsvRows<R> {
+    lines: Lines<R>,
+    headers: Vec<String>,
+}
+
+impl<R: BufRead> CsvRows<R> {
+    /// Read the header line
+    pub fn new(reader: R) -> Result<Self, DatasetError> {
+        let mut lines = reader.lines();
+        let headers = split(&lines.next().ok_or(DatasetError::NoData)??)
+            .into_iter()
+            .skip(1)
+            .collect();
+        Ok(Self { lines, headers })
+    }
+
+    /// Labels of the datasets, the first column excluded
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+}
+
+impl<R: BufRead> Iterator for CsvRows<R> {
+    type Item = Result<(String, Vec<f64>), DatasetError>;
+
+    /// Blank lines are skipped
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = loop {
+            match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => break line,
+                Err(err) => return Some(Err(err.into())),
+            }
+        };
+        Some(parse_row(&line))
+    }
+}
+
+/// Split a CSV line into its first column, unparsed, and the values of the others
+pub(crate) fn parse_row(line: &str) -> Result<(String, Vec<f64>), DatasetError> {
+    let mut fields = split(line).into_iter();
+    let x = fields.next().unwrap_or_default();
+    let values = fields
+        .map(|y| y.parse().map_err(DatasetError::from))
+        .collect::<Result<Vec<f64>, _>>()?;
+    Ok((x, values))
+}
+
+fn split(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(|l| String::from(l.replace('"', "").trim()))
+        .collect()
+}
+
+/// Histogram of fixed size whose range grows with the values
+///
+/// When a value is out of range, the bin width is doubled and neighbouring
+/// bins are merged
+#[derive(Debug, Clone)]
+struct AdaptiveHistogram {
+    origin: f64,
+    width: f64,
+    counts: Vec<usize>,
+}
+
+impl AdaptiveHistogram {
+    fn new(origin: f64) -> Self {
+        Self {
+            origin,
+            width: f64::EPSILON * origin.abs().max(1.0),
+            counts: vec![0; FINE_BINS],
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        while value < self.origin {
+            self.grow(true);
+        }
+        while value >= self.origin + self.width * FINE_BINS as f64 {
+            self.grow(false);
+        }
+        let idx = ((value - self.origin) / self.width) as usize;
+        self.counts[idx.min(FINE_BINS - 1)] += 1;
+    }
+
+    /// Double the bin width, the current range becoming the lower or upper half
+    fn grow(&mut self, downward: bool) {
+        let mut counts = vec![0; FINE_BINS];
+        let offset = if downward { FINE_BINS / 2 } else { 0 };
+        for (idx, count) in self.counts.iter().enumerate() {
+            counts[offset + idx / 2] += count;
+        }
+        if downward {
+            self.origin -= self.width * FINE_BINS as f64;
+        }
+        self.width *= 2.0;
+        self.counts = counts;
+    }
+
+    /// (lower boundary, upper boundary, count) of non empty bins
+    fn bins(&self) -> impl Iterator<Item = (f64, f64, usize)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c > 0)
+            .map(|(idx, &c)| {
+                let start = self.origin + idx as f64 * self.width;
+                (start, start + self.width, c)
+            })
+    }
+}
+
+/// Summary of a series updated value by value, in bounded memory
+///
+/// Histograms are approximated with 1024 bins, quantiles with a [QuantileSketch]
+#[derive(Debug, Clone, Default)]
+pub struct Accumulator {
+    count: usize,
+    /// Number of NaN and infinite values ignored
+    skipped: usize,
+    min: f64,
+    max: f64,
+    sum: f64,
+    histogram: Option<AdaptiveHistogram>,
+    sketch: QuantileSketch,
+}
+
+impl Accumulator {
+    /// NaN and infinite values are ignored, as they have no bin
+    pub fn add(&mut self, value: f64) {
+        if !value.is_finite() {
+            self.skipped += 1;
+            return;
+        }
+        if self.count == 0 {
+            (self.min, self.max) = (value, value);
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.histogram
+            .get_or_insert_with(|| AdaptiveHistogram::new(value))
+            .add(value);
+        self.sketch.add(value);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Number of NaN and infinite values ignored
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    /// Approximate quantile (see [QuantileSketch])
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        self.sketch.quantile(q)
+    }
+
+    /// Histogram of 10 bins between the extrema
+    pub fn histogram(&self) -> Histogram {
+        let Some(fine) = &self.histogram else {
+            return Histogram::default();
+        };
+        let mut histogram = Histogram::from_range(self.min, self.max, 10);
+        for (start, end, count) in fine.bins() {
+            let center = ((start + end) / 2.0).clamp(self.min, self.max);
+            if let Some(idx) = histogram.get_bin(center) {
+                histogram.values[idx] += count;
+            }
+        }
+        histogram
+    }
+
+    /// Approximate cumulative distribution function (see [QuantileSketch])
+    pub fn cdf(&self) -> CDF {
+        self.sketch.cdf()
+    }
+
+    /// Quartiles and whiskers, outliers being not kept
+    pub fn quantiles(&self) -> Option<Quantiles> {
+        self.sketch.quantiles()
+    }
+}
+
+/// Summaries of the datasets of a CSV document, read without keeping the points
+#[derive(Debug, Default)]
+pub struct StreamedDataSet {
+    /// (label, accumulator), in the order of the columns
+    accumulators: Vec<(String, Accumulator)>,
+}
+
+impl StreamedDataSet {
+    /// Read a CSV document (see [crate::DataSet::from_csv]), the first column
+    /// being ignored
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, DatasetError> {
+        let rows = CsvRows::new(reader)?;
+        let mut accumulators: Vec<(String, Accumulator)> = rows
+            .headers()
+            .iter()
+            .map(|label| (label.clone(), Accumulator::default()))
+            .collect();
+        for row in rows {
+            let (_, values) = row?;
+            for ((_, accumulator), value) in accumulators.iter_mut().zip(values) {
+                accumulator.add(value);
+            }
+        }
+        Ok(Self { accumulators })
+    }
+
+    pub fn accumulators(&self) -> &[(String, Accumulator)] {
+        &self.accumulators
+    }
+
+    /// Only distributions can be drawn: histogram, CDF, CCDF and boxplot
+    pub fn draw_into(&self, canvas: &mut Canvas, kind: PlotKind) -> Result<(), CanvasError> {
+        let accumulators = self.accumulators.iter().filter(|(_, a)| a.count() > 0);
+        match kind {
+            PlotKind::Histogram => {
+                let hists: Vec<(char, Option<Color>, Histogram)> = accumulators
+                    .map(|(label, a)| (symbole(label), None, a.histogram()))
+                    .collect();
+                draw_histograms(canvas, &hists)
+            }
+            PlotKind::CDF | PlotKind::CCDF => {
+                let nb = accumulators.clone().map(|(_, a)| a.count()).max();
+                let cdfs = accumulators
+                    .map(|(label, a)| (symbole(label), None, a.cdf()))
+                    .collect();
+                draw_cdfs(
+                    canvas,
+                    cdfs,
+                    matches!(kind, PlotKind::CCDF),
+                    nb.unwrap_or(1),
+                )
+            }
+            PlotKind::Boxplot => {
+                let (x_min, x_max) = accumulators
+                    .clone()
+                    .map(|(_, a)| (a.min, a.max))
+                    .reduce(|(a, b), (c, d)| (a.min(c), b.max(d)))
+                    .ok_or(CanvasError::NoData)?;
+                if x_min < x_max {
+                    canvas.set_x_range(x_min, x_max);
+                } else {
+                    canvas.set_x_range(x_min - 1.0, x_max + 1.0);
+                }
+                for (idx, (_, a)) in accumulators.enumerate() {
+                    if let Some(q) = a.quantiles() {
+                        q.draw_into(canvas, 4 * idx)?;
+                    }
+                }
+                Ok(())
+            }
+            kind => Err(CanvasError::Unsupported(format!("streamed {kind} plot"))),
+        }
+    }
+}
+
+/// First letter of a label
+fn symbole(label: &str) -> char {
+    label.chars().next().expect("label should not be empty")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_rows() {
+        let text = "x, a, \"b\"\n0,1,2\n\n1,3,4\n2,x,5\n";
+        let mut rows = CsvRows::new(text.as_bytes()).unwrap();
+        assert_eq!(rows.headers(), ["a", "b"]);
+        assert_eq!(rows.next().unwrap().unwrap(), ("0".into(), vec![1.0, 2.0]));
+        assert_eq!(rows.next().unwrap().unwrap(), ("1".into(), vec![3.0, 4.0]));
+        assert!(rows.next().unwrap().is_err());
+        assert!(rows.next().is_none());
+        assert!(CsvRows::new("".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn accumulator() {
+        let mut a = Accumulator::default();
+        assert_eq!(a.quantile(0.5), None);
+        for x in (0..=1000).rev() {
+            a.add(x as f64);
+        }
+        a.add(f64::NAN);
+        assert_eq!(a.count(), 1001);
+        assert_eq!(a.skipped(), 1);
+        assert_eq!((a.min(), a.max()), (Some(0.0), Some(1000.0)));
+        assert_eq!(a.mean(), Some(500.0));
+        // rank error of the sketch
+        for q in [0.1, 0.25, 0.5, 0.9] {
+            assert!((a.quantile(q).unwrap() - 1000.0 * q).abs() < 16.5);
+        }
+        let hist = a.histogram();
+        assert_eq!(hist.values.iter().sum::<usize>(), 1001);
+        assert!((hist.get_value(50.0).unwrap() - 101.0).abs() <= 2.0);
+        assert!((a.cdf().get_value(500.0) - 0.5).abs() < 0.0165);
+    }
+
+    #[test]
+    fn streamed_dataset() {
+        let text = "x,a,b\n0,1,-1\n1,3,-3\n2,2,-2\n";
+        let streamed = StreamedDataSet::from_reader(text.as_bytes()).unwrap();
+        let [(a, acc_a), (b, _)] = streamed.accumulators() else {
+            panic!("two datasets expected");
+        };
+        assert_eq!((a.as_str(), b.as_str()), ("a", "b"));
+        assert_eq!(acc_a.mean(), Some(2.0));
+
+        let mut canvas = Canvas::new();
+        streamed.draw_into(&mut canvas, PlotKind::Boxplot).unwrap();
+        assert!(streamed.draw_into(&mut canvas, PlotKind::Point).is_err());
+    }
+
+    #[test]
+    fn streamed_infinite() {
+        let text = "x,a\n0,1\n1,inf\n2,-inf\n3,2\n";
+        // a single finite value
+        let single = StreamedDataSet::from_reader("x,a\n0,1\n1,inf\n".as_bytes()).unwrap();
+        let mut canvas = Canvas::new();
+        single.draw_into(&mut canvas, PlotKind::Histogram).unwrap();
+
+        let streamed = StreamedDataSet::from_reader(text.as_bytes()).unwrap();
+        let (_, a) = &streamed.accumulators()[0];
+        assert_eq!((a.count(), a.skipped()), (2, 2));
+        assert_eq!((a.min(), a.max()), (Some(1.0), Some(2.0)));
+        for kind in [PlotKind::Histogram, PlotKind::CDF, PlotKind::Boxplot] {
+            let mut canvas = Canvas::new();
+            streamed.draw_into(&mut canvas, kind).unwrap();
+        }
+    }
+}
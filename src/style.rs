@@ -0,0 +1,227 @@
+use crate::{Canvas, CanvasError, Color, DataSet};
+use clap::ValueEnum;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// How consecutive points of a dataset are linked
+#[derive(Debug, Default, Copy, Clone, PartialEq, ValueEnum)]
+pub enum LineStyle {
+    /// Points only
+    #[default]
+    None,
+
+    /// Continuous line
+    Solid,
+
+    /// Line interrupted every other pair of columns
+    Dashed,
+
+    /// Line drawn every other column
+    Dotted,
+}
+
+impl Display for LineStyle {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            LineStyle::None => write!(f, "none"),
+            LineStyle::Solid => write!(f, "solid"),
+            LineStyle::Dashed => write!(f, "dashed"),
+            LineStyle::Dotted => write!(f, "dotted"),
+        }
+    }
+}
+
+/// Appearance of a dataset
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    /// Symbol of the dataset, the first letter of its label by default
+    pub marker: Option<char>,
+    /// Color of the symbol, the terminal one by default
+    pub color: Option<Color>,
+    /// Lines between consecutive points of point plots
+    pub line: LineStyle,
+    /// Whether the dataset is drawn
+    pub visible: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            marker: None,
+            color: None,
+            line: LineStyle::None,
+            visible: true,
+        }
+    }
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    /// Items separated by `:`, in any order: a single character for the
+    /// marker, a color, a line style, `hidden`, such as `*:red` or `o:blue:dashed`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = Self::default();
+        for item in s.split(':').filter(|i| !i.is_empty()) {
+            let mut chars = item.chars();
+            if let (Some(marker), None) = (chars.next(), chars.next()) {
+                style.marker = Some(marker);
+            } else if item == "hidden" {
+                style.visible = false;
+            } else if let Ok(line) = LineStyle::from_str(item, true) {
+                style.line = line;
+            } else if let Ok(color) = item.parse() {
+                style.color = Some(color);
+            } else {
+                return Err(format!(
+                    "invalid style item '{item}': expected a character, a color, a line style or hidden"
+                ));
+            }
+        }
+        Ok(style)
+    }
+}
+
+/// Style of a dataset
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleMapping {
+    pub label: String,
+    pub style: Style,
+}
+
+impl FromStr for StyleMapping {
+    type Err = String;
+
+    /// `label=style`, such as `latency=*:red`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (label, style) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid style '{s}': expected label=style"))?;
+        Ok(Self {
+            label: label.to_string(),
+            style: style.parse()?,
+        })
+    }
+}
+
+impl DataSet {
+    pub fn set_style(&mut self, label: &str, style: Style) {
+        self.styles.insert(label.to_string(), style);
+    }
+
+    /// Style of a dataset, the default one if not set
+    pub fn style(&self, label: &str) -> Style {
+        self.styles.get(label).cloned().unwrap_or_default()
+    }
+
+    /// Replace the marker of a dataset, keeping the rest of its style
+    pub(crate) fn set_marker(&mut self, label: &str, marker: char) {
+        self.styles.entry(label.to_string()).or_default().marker = Some(marker);
+    }
+}
+
+impl Canvas {
+    /// Link consecutive points, in x order, with the symbol
+    pub(crate) fn draw_polyline(
+        &mut self,
+        points: &[(f64, f64)],
+        line: LineStyle,
+        value: char,
+    ) -> Result<(), CanvasError> {
+        let mut sorted: Vec<(f64, f64)> = points
+            .iter()
+            .copied()
+            .filter(|p| !p.0.is_nan() && !p.1.is_nan())
+            .collect();
+        if line == LineStyle::None || sorted.len() < 2 {
+            return Ok(());
+        }
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let (first, last) = (sorted[0].0, sorted[sorted.len() - 1].0);
+        let interpolate = |x: f64| {
+            if x < first || x > last {
+                return f64::NAN;
+            }
+            let idx = sorted
+                .partition_point(|p| p.0 <= x)
+                .clamp(1, sorted.len() - 1);
+            let (a, b) = (sorted[idx - 1], sorted[idx]);
+            if a.0 == b.0 {
+                b.1
+            } else {
+                a.1 + (x - a.0) * (b.1 - a.1) / (b.0 - a.0)
+            }
+        };
+        let pattern = |column: usize| match line {
+            LineStyle::None => false,
+            LineStyle::Solid => true,
+            LineStyle::Dashed => column % 4 < 2,
+            LineStyle::Dotted => column.is_multiple_of(2),
+        };
+        self.draw_pattern(interpolate, value, pattern)
+    }
+
+    /// Color of the next cells set, that of the dataset being drawn
+    pub(crate) fn set_pen(&mut self, color: Option<Color>) {
+        self.pen = color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlotKind;
+
+    #[test]
+    fn style_parse() {
+        let mapping: StyleMapping = "latency=*:red:dashed".parse().unwrap();
+        assert_eq!(mapping.label, "latency");
+        assert_eq!(mapping.style.marker, Some('*'));
+        assert_eq!(mapping.style.color, Some(Color::Ansi256(1)));
+        assert_eq!(mapping.style.line, LineStyle::Dashed);
+        let style: Style = "●:#ff8000:hidden".parse().unwrap();
+        assert_eq!(style.marker, Some('●'));
+        assert_eq!(style.color, Some(Color::Rgb(255, 128, 0)));
+        assert!(!style.visible);
+        assert!("latency".parse::<StyleMapping>().is_err());
+        assert!("latency=wavy".parse::<StyleMapping>().is_err());
+    }
+
+    #[test]
+    fn dataset_styles() {
+        let mut dataset = DataSet::from_csv("x,a,b\n0,0,1\n10,10,5\n").unwrap();
+        dataset.set_style("a", "●:red:solid".parse().unwrap());
+        dataset.set_style("b", "hidden".parse().unwrap());
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        let text = canvas.to_string();
+        assert!(!text.contains('b'));
+        // a line of markers, each one being red
+        assert!(text.matches('●').count() > 70);
+        assert_eq!(
+            text.matches("\x1b[38;5;1m●").count(),
+            text.matches('●').count()
+        );
+    }
+
+    #[test]
+    fn colors_by_dataset() {
+        let mut dataset = DataSet::from_csv("x,a,b\n0,0,1\n10,10,5\n").unwrap();
+        dataset.set_style("a", "*:red".parse().unwrap());
+        dataset.set_style("b", "*:blue".parse().unwrap());
+        dataset.add_annotation(crate::Annotation::Text(crate::Note {
+            x: crate::Abscissa::Number(1.0),
+            y: 5.5,
+            text: "baseline".to_string(),
+        }));
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        let text = canvas.to_string();
+        // same marker, each dataset keeping its color
+        assert_eq!(text.matches("\x1b[38;5;1m*").count(), 2);
+        assert_eq!(text.matches("\x1b[38;5;4m*").count(), 2);
+        // text is not colored
+        assert!(text.contains("baseline"));
+    }
+}
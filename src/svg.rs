@@ -0,0 +1,151 @@
+use crate::{Canvas, Color};
+use std::fmt::Write;
+
+/// Size of a cell in SVG output, in pixels
+const CELL_WIDTH: f64 = 8.0;
+const CELL_HEIGHT: f64 = 16.0;
+/// Font size whose characters are about as wide as a cell
+const FONT_SIZE: f64 = 13.0;
+
+/// Area shaded between two curves, only drawn in SVG output
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Band {
+    color: Option<Color>,
+    /// (column, line of the lower bound, line of the upper bound),
+    /// as fractional cells sorted by column
+    points: Vec<(f64, f64, f64)>,
+}
+
+impl Canvas {
+    /// Record a band shaded between low and high at each x, with the pen color
+    ///
+    /// Bands have no character representation, they only appear in
+    /// [Canvas::to_svg].
+    pub(crate) fn add_band(&mut self, intervals: &[(f64, f64, f64)]) {
+        let ((x_min, x_max), (y_min, y_max)) = (self.x_range, self.y_range);
+        if !(x_min < x_max && y_min < y_max) {
+            return;
+        }
+        // same positions as get_cell and get_line, without rounding
+        let column = |x: f64| (x - x_min) / (x_max - x_min) * (self.width - 1) as f64;
+        let line = |y: f64| {
+            let t = if self.log_y {
+                (y.log10() - y_min.log10()) / (y_max.log10() - y_min.log10())
+            } else {
+                (y - y_min) / (y_max - y_min)
+            };
+            self.height as f64 - t * (self.height - 1) as f64
+        };
+        let mut points: Vec<(f64, f64, f64)> = intervals
+            .iter()
+            .map(|&(x, low, high)| (column(x), line(low), line(high)))
+            .filter(|p| p.0.is_finite() && p.1.is_finite() && p.2.is_finite())
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if points.len() > 1 {
+            self.bands.push(Band {
+                color: self.pen,
+                points,
+            });
+        }
+    }
+
+    /// SVG document of the canvas, each cell holding a character of a
+    /// monospace font, bands being shaded below the characters
+    pub fn to_svg(&self) -> String {
+        let (width, height) = (
+            self.width as f64 * CELL_WIDTH,
+            self.height as f64 * CELL_HEIGHT,
+        );
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             font-family=\"monospace\" font-size=\"{FONT_SIZE}\">\n"
+        );
+        svg += "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n";
+        let (x, y) = (
+            |column: f64| (column + 0.5) * CELL_WIDTH,
+            |line: f64| (line + 0.5) * CELL_HEIGHT,
+        );
+        for band in &self.bands {
+            let upper = band.points.iter().map(|p| (p.0, p.2));
+            let lower = band.points.iter().rev().map(|p| (p.0, p.1));
+            let outline: Vec<String> = upper
+                .chain(lower)
+                .map(|(c, l)| format!("{:.1},{:.1}", x(c), y(l)))
+                .collect();
+            let _ = writeln!(
+                svg,
+                "<polygon points=\"{}\" fill=\"{}\" fill-opacity=\"0.3\"/>",
+                outline.join(" "),
+                css(band.color.unwrap_or(Color::Ansi256(8)))
+            );
+        }
+        for (line, cells) in self.cells.iter().enumerate() {
+            for (column, &c) in cells.iter().enumerate() {
+                let (left, top) = (column as f64 * CELL_WIDTH, line as f64 * CELL_HEIGHT);
+                if let Some(background) = self.backgrounds[line][column] {
+                    let _ = writeln!(
+                        svg,
+                        "<rect x=\"{left}\" y=\"{top}\" width=\"{CELL_WIDTH}\" height=\"{CELL_HEIGHT}\" fill=\"{}\"/>",
+                        css(background)
+                    );
+                }
+                if c == ' ' {
+                    continue;
+                }
+                let fill = self.foregrounds[line][column].map_or("black".into(), css);
+                let text = match c {
+                    '&' => "&amp;".into(),
+                    '<' => "&lt;".into(),
+                    '>' => "&gt;".into(),
+                    c => c.to_string(),
+                };
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{left}\" y=\"{}\" fill=\"{fill}\">{text}</text>",
+                    top + 0.75 * CELL_HEIGHT
+                );
+            }
+        }
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+/// CSS notation of a color
+fn css(color: Color) -> String {
+    let (r, g, b) = color.rgb();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DataSet, ErrorSpec, PlotKind};
+
+    #[test]
+    fn svg_bands() {
+        let text = "x,mean,sd\n0,1,0.5\n1,2,1\n2,4,1\n";
+        let mut dataset = DataSet::from_csv(text).unwrap();
+        dataset.set_style("mean", "m:red".parse().unwrap());
+        dataset
+            .set_y_error("mean", ErrorSpec::Symmetric("sd".into()))
+            .unwrap();
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        let svg = canvas.to_svg();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // one band, colored as the dataset, with a point per bound
+        let band = svg.lines().find(|l| l.starts_with("<polygon")).unwrap();
+        assert!(band.contains("fill=\"#cd0000\""));
+        assert_eq!(band.matches(',').count(), 6);
+        assert_eq!(svg.matches(">m</text>").count(), 3);
+
+        // without uncertainty, no band
+        let dataset = DataSet::from_csv("x,a\n0,1\n1,2\n").unwrap();
+        let mut canvas = Canvas::new();
+        dataset.draw_into(&mut canvas, PlotKind::Point).unwrap();
+        assert!(!canvas.to_svg().contains("<polygon"));
+    }
+}
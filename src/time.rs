@@ -0,0 +1,383 @@
+use clap::ValueEnum;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Unit of unix timestamps
+#[derive(Debug, Default, Copy, Clone, PartialEq, ValueEnum)]
+pub enum TimeUnit {
+    /// Seconds
+    #[default]
+    #[value(name = "s")]
+    Seconds,
+
+    /// Milliseconds
+    #[value(name = "ms")]
+    Milliseconds,
+
+    /// Microseconds
+    #[value(name = "us")]
+    Microseconds,
+}
+
+impl TimeUnit {
+    /// Number of units in a second
+    pub fn per_second(&self) -> f64 {
+        match self {
+            TimeUnit::Seconds => 1.0,
+            TimeUnit::Milliseconds => 1e3,
+            TimeUnit::Microseconds => 1e6,
+        }
+    }
+}
+
+impl Display for TimeUnit {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            TimeUnit::Seconds => write!(f, "s"),
+            TimeUnit::Milliseconds => write!(f, "ms"),
+            TimeUnit::Microseconds => write!(f, "us"),
+        }
+    }
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Number of days of a month of the proleptic Gregorian calendar
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Date (year, month, day) of a number of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Consume exactly `len` digits
+fn take_number(s: &mut &str, len: usize) -> Option<u32> {
+    let digits = s.get(..len)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    *s = &s[len..];
+    digits.parse().ok()
+}
+
+/// Consume a specific character
+fn take_char(s: &mut &str, c: char) -> bool {
+    if let Some(rest) = s.strip_prefix(c) {
+        *s = rest;
+        true
+    } else {
+        false
+    }
+}
+
+/// Parse a date into seconds since 1970-01-01T00:00:00Z
+///
+/// Accepted formats are RFC 3339 and its common variants:
+/// `2024-03-01`, `2024/03/01 12:30`, `2024-03-01T12:30:15.25+02:00`, ...
+/// Dates without offset are considered as UTC.
+pub fn parse_time(input: &str) -> Option<f64> {
+    let mut s = input.trim();
+    let year = take_number(&mut s, 4)?;
+    let sep = if s.starts_with('/') { '/' } else { '-' };
+    if !take_char(&mut s, sep) {
+        return None;
+    }
+    let month = take_number(&mut s, 2)?;
+    if !take_char(&mut s, sep) {
+        return None;
+    }
+    let day = take_number(&mut s, 2)?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year as i64, month)).contains(&day) {
+        return None;
+    }
+    let mut seconds = days_from_civil(year as i64, month, day) as f64 * DAY;
+    if s.is_empty() {
+        return Some(seconds);
+    }
+
+    if !(take_char(&mut s, 'T') || take_char(&mut s, 't') || take_char(&mut s, ' ')) {
+        return None;
+    }
+    let hour = take_number(&mut s, 2)?;
+    if !take_char(&mut s, ':') {
+        return None;
+    }
+    let minute = take_number(&mut s, 2)?;
+    let second = if take_char(&mut s, ':') {
+        take_number(&mut s, 2)?
+    } else {
+        0
+    };
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    seconds += hour as f64 * HOUR + minute as f64 * MINUTE + second as f64;
+    if take_char(&mut s, '.') || take_char(&mut s, ',') {
+        let len = s.bytes().take_while(|b| b.is_ascii_digit()).count();
+        if len == 0 {
+            return None;
+        }
+        seconds += format!("0.{}", &s[..len]).parse::<f64>().ok()?;
+        s = &s[len..];
+    }
+
+    if s.is_empty() || s == "Z" || s == "z" {
+        return Some(seconds);
+    }
+    let sign = if take_char(&mut s, '+') {
+        1.0
+    } else if take_char(&mut s, '-') {
+        -1.0
+    } else {
+        return None;
+    };
+    let offset_hour = take_number(&mut s, 2)?;
+    take_char(&mut s, ':');
+    let offset_minute = take_number(&mut s, 2)?;
+    if !s.is_empty() {
+        return None;
+    }
+    Some(seconds - sign * (offset_hour as f64 * HOUR + offset_minute as f64 * MINUTE))
+}
+
+/// Format seconds since 1970-01-01T00:00:00Z
+///
+/// Supported fields are `%Y`, `%y`, `%m`, `%b`, `%d`, `%H`, `%M`, `%S` and `%%`.
+pub fn format_time(time: f64, format: &str) -> String {
+    let days = (time / DAY).floor();
+    let (year, month, day) = civil_from_days(days as i64);
+    let seconds = (time - days * DAY).floor() as u32;
+    let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let mut out = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => out.push_str(&year.to_string()),
+            Some('y') => out.push_str(&format!("{:02}", year.rem_euclid(100))),
+            Some('m') => out.push_str(&format!("{month:02}")),
+            Some('b') => out.push_str(MONTHS[month as usize - 1]),
+            Some('d') => out.push_str(&format!("{day:02}")),
+            Some('H') => out.push_str(&format!("{hour:02}")),
+            Some('M') => out.push_str(&format!("{minute:02}")),
+            Some('S') => out.push_str(&format!("{second:02}")),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+/// Interval between two ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    Seconds(f64),
+    Months(u32),
+}
+
+const STEPS: [Step; 24] = [
+    Step::Seconds(1.0),
+    Step::Seconds(2.0),
+    Step::Seconds(5.0),
+    Step::Seconds(10.0),
+    Step::Seconds(15.0),
+    Step::Seconds(30.0),
+    Step::Seconds(MINUTE),
+    Step::Seconds(2.0 * MINUTE),
+    Step::Seconds(5.0 * MINUTE),
+    Step::Seconds(10.0 * MINUTE),
+    Step::Seconds(15.0 * MINUTE),
+    Step::Seconds(30.0 * MINUTE),
+    Step::Seconds(HOUR),
+    Step::Seconds(2.0 * HOUR),
+    Step::Seconds(3.0 * HOUR),
+    Step::Seconds(6.0 * HOUR),
+    Step::Seconds(12.0 * HOUR),
+    Step::Seconds(DAY),
+    Step::Seconds(2.0 * DAY),
+    Step::Seconds(7.0 * DAY),
+    Step::Months(1),
+    Step::Months(3),
+    Step::Months(12),
+    Step::Months(120),
+];
+
+impl Step {
+    /// Approximate duration, in seconds
+    fn duration(&self) -> f64 {
+        match self {
+            Step::Seconds(s) => *s,
+            Step::Months(m) => *m as f64 * 30.4 * DAY,
+        }
+    }
+
+    /// Default label format for ticks at this interval
+    fn format(&self, span: f64) -> &'static str {
+        match self {
+            Step::Seconds(s) if *s < MINUTE => "%H:%M:%S",
+            Step::Seconds(s) if *s < DAY && span > DAY => "%d %H:%M",
+            Step::Seconds(s) if *s < DAY => "%H:%M",
+            Step::Seconds(_) => "%Y-%m-%d",
+            Step::Months(m) if *m < 12 => "%Y-%m",
+            Step::Months(_) => "%Y",
+        }
+    }
+
+    /// Ticks, aligned on calendar boundaries, between start and end
+    fn ticks(&self, start: f64, end: f64) -> Vec<f64> {
+        match self {
+            Step::Seconds(s) if *s == 7.0 * DAY => {
+                // weeks start on monday, 1970-01-05
+                let monday = 4.0 * DAY;
+                let first = ((start - monday) / s).ceil() * s + monday;
+                (0..)
+                    .map(|i| first + i as f64 * s)
+                    .take_while(|&t| t <= end)
+                    .collect()
+            }
+            Step::Seconds(s) => {
+                let first = (start / s).ceil() * s;
+                (0..)
+                    .map(|i| first + i as f64 * s)
+                    .take_while(|&t| t <= end)
+                    .collect()
+            }
+            Step::Months(m) => {
+                let m = *m as i64;
+                let (year, month, _) = civil_from_days((start / DAY).floor() as i64);
+                // months since year 0, rounded up to the step
+                let mut months = (year * 12 + month as i64 - 1 + m - 1).div_euclid(m) * m;
+                let mut ticks = Vec::new();
+                loop {
+                    let t =
+                        days_from_civil(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1)
+                            as f64
+                            * DAY;
+                    if t > end {
+                        break;
+                    }
+                    if t >= start {
+                        ticks.push(t);
+                    }
+                    months += m;
+                }
+                ticks
+            }
+        }
+    }
+}
+
+/// Choose tick positions at calendar boundaries between start and end
+///
+/// Return the ticks and a suitable label format
+/// The chosen interval is the smallest one leading to at most max_ticks ticks
+pub fn time_ticks(start: f64, end: f64, max_ticks: usize) -> (Vec<f64>, &'static str) {
+    let span = end - start;
+    let step = STEPS
+        .iter()
+        .find(|step| span / step.duration() <= max_ticks.max(1) as f64)
+        .unwrap_or(&STEPS[STEPS.len() - 1]);
+    (step.ticks(start, end), step.format(span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_roundtrip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        for days in [-1000, 0, 11016, 11017, 19782] {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+    }
+
+    #[test]
+    fn time_parse() {
+        assert_eq!(parse_time("1970-01-02"), Some(DAY));
+        assert_eq!(parse_time("2024-03-01T12:30:15Z"), Some(1709296215.0));
+        assert_eq!(parse_time("2024/03/01 12:30:15"), Some(1709296215.0));
+        assert_eq!(
+            parse_time("2024-03-01 14:30:15.5+02:00"),
+            Some(1709296215.5)
+        );
+        assert_eq!(parse_time("2024-03-01T07:30-0500"), Some(1709296200.0));
+        assert_eq!(parse_time("2024-13-01"), None);
+        assert_eq!(parse_time("2024-02-31"), None);
+        assert_eq!(parse_time("2023-02-29"), None);
+        assert_eq!(parse_time("2024-04-31T12:00"), None);
+        assert_eq!(parse_time("2024-02-29"), Some(1709164800.0));
+        assert_eq!(parse_time("2000-02-29"), Some(951782400.0));
+        assert_eq!(parse_time("1900-02-29"), None);
+        assert_eq!(parse_time("api"), None);
+        assert_eq!(parse_time("1709296215"), None);
+    }
+
+    #[test]
+    fn time_format() {
+        let t = 1709296215.0;
+        assert_eq!(format_time(t, "%Y-%m-%dT%H:%M:%S"), "2024-03-01T12:30:15");
+        assert_eq!(format_time(t, "%d %b %y, 100%%"), "01 Mar 24, 100%");
+        assert_eq!(
+            format_time(-1.0, "%Y-%m-%d %H:%M:%S"),
+            "1969-12-31 23:59:59"
+        );
+    }
+
+    #[test]
+    fn ticks() {
+        let start = parse_time("2024-03-01T12:07:00Z").unwrap();
+        let end = parse_time("2024-03-01T13:02:00Z").unwrap();
+        let (ticks, format) = time_ticks(start, end, 4);
+        assert_eq!(format, "%H:%M");
+        let labels: Vec<String> = ticks.iter().map(|&t| format_time(t, format)).collect();
+        assert_eq!(labels, ["12:15", "12:30", "12:45", "13:00"]);
+
+        let end = parse_time("2024-09-15").unwrap();
+        let (ticks, format) = time_ticks(start, end, 4);
+        let labels: Vec<String> = ticks.iter().map(|&t| format_time(t, format)).collect();
+        assert_eq!(labels, ["2024-04", "2024-07"]);
+    }
+}
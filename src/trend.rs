@@ -0,0 +1,336 @@
+use crate::{Canvas, CanvasError};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::iter::zip;
+use std::str::FromStr;
+
+/// Model fitted on a series
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Fit {
+    /// y = a + b x
+    Linear,
+
+    /// y = a + b x + c x² + ..., of a specific degree
+    Polynomial(usize),
+
+    /// y = a e^(b x), for positive y
+    Exponential,
+
+    /// y = a x^b, for positive x and y
+    Power,
+}
+
+impl Display for Fit {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Fit::Linear => write!(f, "linear"),
+            Fit::Polynomial(degree) => write!(f, "poly{degree}"),
+            Fit::Exponential => write!(f, "exp"),
+            Fit::Power => write!(f, "power"),
+        }
+    }
+}
+
+impl FromStr for Fit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(Fit::Linear),
+            "exp" | "exponential" => Ok(Fit::Exponential),
+            "power" => Ok(Fit::Power),
+            _ => s
+                .strip_prefix("poly")
+                .and_then(|d| d.parse().ok())
+                .filter(|&d| d > 0)
+                .map(Fit::Polynomial)
+                .ok_or_else(|| format!("invalid fit '{s}': expected linear, polyN, exp or power")),
+        }
+    }
+}
+
+/// Model fitted on a series with least squares
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trend {
+    fit: Fit,
+    /// Polynomial coefficients, from the constant term,
+    /// or (a, b) for exponential and power fits
+    coefficients: Vec<f64>,
+    /// Polynomial fitted, evaluated on scaled x to keep its precision
+    polynomial: Polynomial,
+    /// Coefficient of determination, computed on y
+    r2: f64,
+}
+
+impl Trend {
+    /// Fit a model on points, ignoring NaN and points outside of its domain
+    ///
+    /// Return None if there are not enough points to determine the model
+    pub fn from_points(points: &[(f64, f64)], fit: Fit) -> Option<Self> {
+        let points: Vec<(f64, f64)> = points
+            .iter()
+            .copied()
+            .filter(|(x, y)| !x.is_nan() && !y.is_nan())
+            .filter(|&(x, y)| match fit {
+                Fit::Linear | Fit::Polynomial(_) => true,
+                Fit::Exponential => y > 0.0,
+                Fit::Power => x > 0.0 && y > 0.0,
+            })
+            .collect();
+        // fit a polynomial on transformed coordinates
+        let (degree, transformed): (usize, Vec<(f64, f64)>) = match fit {
+            Fit::Linear => (1, points.clone()),
+            Fit::Polynomial(degree) => (degree, points.clone()),
+            Fit::Exponential => (1, points.iter().map(|&(x, y)| (x, y.ln())).collect()),
+            Fit::Power => (1, points.iter().map(|&(x, y)| (x.ln(), y.ln())).collect()),
+        };
+        let polynomial = Polynomial::fit(&transformed, degree)?;
+        let mut coefficients = polynomial.unscaled();
+        if matches!(fit, Fit::Exponential | Fit::Power) {
+            coefficients[0] = coefficients[0].exp();
+        }
+        let mut trend = Self {
+            fit,
+            coefficients,
+            polynomial,
+            r2: f64::NAN,
+        };
+        let mean = points.iter().map(|p| p.1).sum::<f64>() / points.len() as f64;
+        let total: f64 = points.iter().map(|p| (p.1 - mean).powi(2)).sum();
+        let residual: f64 = points
+            .iter()
+            .map(|&(x, y)| (y - trend.get_value(x)).powi(2))
+            .sum();
+        trend.r2 = if total > 0.0 {
+            1.0 - residual / total
+        } else {
+            1.0
+        };
+        Some(trend)
+    }
+
+    pub fn fit(&self) -> Fit {
+        self.fit
+    }
+
+    /// Polynomial coefficients from the constant term, or (a, b) for
+    /// exponential and power fits
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    /// Coefficient of determination
+    pub fn r2(&self) -> f64 {
+        self.r2
+    }
+
+    /// Value of the model at a specific point
+    pub fn get_value(&self, x: f64) -> f64 {
+        let c = &self.coefficients;
+        match self.fit {
+            Fit::Linear | Fit::Polynomial(_) => self.polynomial.get_value(x),
+            Fit::Exponential => c[0] * (c[1] * x).exp(),
+            Fit::Power if x < 0.0 => f64::NAN,
+            Fit::Power => c[0] * x.powf(c[1]),
+        }
+    }
+
+    pub fn draw_into(&self, canvas: &mut Canvas, symbole: char) -> Result<(), CanvasError> {
+        canvas.draw_curve(|x| self.get_value(x), symbole)
+    }
+}
+
+impl Display for Trend {
+    /// Formula of the model, followed by its R²
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let c = &self.coefficients;
+        write!(f, "y = ")?;
+        match self.fit {
+            Fit::Linear | Fit::Polynomial(_) => {
+                for (power, a) in c.iter().enumerate().rev() {
+                    let sign = if a.is_sign_negative() { "-" } else { "+" };
+                    match (power, power == c.len() - 1) {
+                        (_, true) if a.is_sign_negative() => write!(f, "-")?,
+                        (_, true) => (),
+                        _ => write!(f, " {sign} ")?,
+                    }
+                    match power {
+                        0 => write!(f, "{:.4}", a.abs())?,
+                        1 => write!(f, "{:.4}x", a.abs())?,
+                        _ => write!(f, "{:.4}x^{power}", a.abs())?,
+                    }
+                }
+            }
+            Fit::Exponential => write!(f, "{:.4} exp({:.4}x)", c[0], c[1])?,
+            Fit::Power => write!(f, "{:.4} x^{:.4}", c[0], c[1])?,
+        }
+        write!(f, " (R² = {:.4})", self.r2)
+    }
+}
+
+/// Polynomial of (x - center) / scale, from the constant term
+#[derive(Debug, Clone, PartialEq)]
+struct Polynomial {
+    center: f64,
+    scale: f64,
+    coefficients: Vec<f64>,
+}
+
+impl Polynomial {
+    /// Least squares fit, x being centered and scaled so that large x
+    /// (e.g. timestamps) keep the normal equations well conditioned
+    ///
+    /// Solve the normal equations with Gaussian elimination
+    fn fit(points: &[(f64, f64)], degree: usize) -> Option<Self> {
+        let n = degree + 1;
+        if points.len() < n {
+            return None;
+        }
+        let nb = points.len() as f64;
+        let center = points.iter().map(|p| p.0).sum::<f64>() / nb;
+        let scale = (points.iter().map(|p| (p.0 - center).powi(2)).sum::<f64>() / nb).sqrt();
+        if scale.partial_cmp(&0.0) != Some(Ordering::Greater) {
+            return None;
+        }
+        // augmented matrix of the normal equations
+        let mut m = vec![vec![0.0; n + 1]; n];
+        for &(x, y) in points {
+            let x = (x - center) / scale;
+            for (i, row) in m.iter_mut().enumerate() {
+                for (j, cell) in row.iter_mut().take(n).enumerate() {
+                    *cell += x.powi((i + j) as i32);
+                }
+                row[n] += y * x.powi(i as i32);
+            }
+        }
+        // pivots are compared to the magnitude of the matrix
+        let tolerance = 1e-12
+            * m.iter()
+                .flat_map(|r| &r[..n])
+                .fold(0.0, |a, b| b.abs().max(a));
+        for col in 0..n {
+            let pivot = (col..n).max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))?;
+            if m[pivot][col].abs() <= tolerance {
+                return None;
+            }
+            m.swap(col, pivot);
+            for row in 0..n {
+                if row != col {
+                    let factor = m[row][col] / m[col][col];
+                    let pivot_row = m[col].clone();
+                    for (cell, p) in zip(&mut m[row][col..], &pivot_row[col..]) {
+                        *cell -= factor * p;
+                    }
+                }
+            }
+        }
+        Some(Self {
+            center,
+            scale,
+            coefficients: (0..n).map(|i| m[i][n] / m[i][i]).collect(),
+        })
+    }
+
+    fn get_value(&self, x: f64) -> f64 {
+        let x = (x - self.center) / self.scale;
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |acc, a| acc * x + a)
+    }
+
+    /// Coefficients of x, from the constant term
+    fn unscaled(&self) -> Vec<f64> {
+        // Horner's method on polynomials, multiplying by (x - center) / scale
+        let mut unscaled = vec![0.0; self.coefficients.len()];
+        for &a in self.coefficients.iter().rev() {
+            for i in (0..unscaled.len()).rev() {
+                let previous = if i > 0 { unscaled[i - 1] } else { 0.0 };
+                unscaled[i] = (previous - self.center * unscaled[i]) / self.scale;
+            }
+            unscaled[0] += a;
+        }
+        unscaled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &[f64], b: &[f64]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-6)
+    }
+
+    #[test]
+    fn fit_parse() {
+        assert_eq!("linear".parse(), Ok(Fit::Linear));
+        assert_eq!("poly3".parse(), Ok(Fit::Polynomial(3)));
+        assert!("poly0".parse::<Fit>().is_err());
+        assert!("cubic".parse::<Fit>().is_err());
+    }
+
+    #[test]
+    fn trend_linear() {
+        let points = [(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (3.0, f64::NAN)];
+        let trend = Trend::from_points(&points, Fit::Linear).unwrap();
+        assert!(close(trend.coefficients(), &[1.0, 2.0]));
+        assert!((trend.r2() - 1.0).abs() < 1e-9);
+        assert_eq!(trend.to_string(), "y = 2.0000x + 1.0000 (R² = 1.0000)");
+        assert_eq!(Trend::from_points(&points[..1], Fit::Linear), None);
+    }
+
+    #[test]
+    fn trend_polynomial() {
+        let points: Vec<(f64, f64)> = (-3..=3)
+            .map(|x| x as f64)
+            .map(|x| (x, 2.0 - x + 0.5 * x * x))
+            .collect();
+        let trend = Trend::from_points(&points, Fit::Polynomial(2)).unwrap();
+        assert!(close(trend.coefficients(), &[2.0, -1.0, 0.5]));
+        assert_eq!(
+            trend.to_string(),
+            "y = 0.5000x^2 - 1.0000x + 2.0000 (R² = 1.0000)"
+        );
+    }
+
+    #[test]
+    fn trend_timestamps() {
+        // one point per minute, from 2023-11-14
+        let points: Vec<(f64, f64)> = (0..60)
+            .map(|i| 1.7e9 + 60.0 * i as f64)
+            .map(|x| {
+                let t = (x - 1.7e9) / 3600.0;
+                (x, 3.0 + 2.0 * t - 0.5 * t * t)
+            })
+            .collect();
+        let trend = Trend::from_points(&points, Fit::Polynomial(2)).unwrap();
+        assert!((trend.r2() - 1.0).abs() < 1e-9);
+        for &(x, y) in &points {
+            assert!((trend.get_value(x) - y).abs() < 1e-6);
+        }
+        let c = trend.coefficients();
+        assert!((c[2] * 3600.0 * 3600.0 + 0.5).abs() < 1e-6);
+        // no solution with a single distinct x
+        assert_eq!(Trend::from_points(&[(1.7e9, 1.0); 3], Fit::Linear), None);
+    }
+
+    #[test]
+    fn trend_exponential_power() {
+        let points: Vec<(f64, f64)> = (1..6)
+            .map(|x| x as f64)
+            .map(|x| (x, 3.0 * (0.5 * x).exp()))
+            .collect();
+        let trend = Trend::from_points(&points, Fit::Exponential).unwrap();
+        assert!(close(trend.coefficients(), &[3.0, 0.5]));
+
+        let points: Vec<(f64, f64)> = (1..6)
+            .map(|x| x as f64)
+            .map(|x| (x, 2.0 * x.powf(1.5)))
+            .collect();
+        let trend = Trend::from_points(&points, Fit::Power).unwrap();
+        assert!(close(trend.coefficients(), &[2.0, 1.5]));
+        assert!((trend.get_value(4.0) - 16.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{Bandwidth, Canvas, CanvasError, Density, Kernel, Quantiles};
+
+/// Density estimate mirrored around the quartiles
+#[derive(Debug, Default)]
+pub struct Violin {
+    density: Density,
+    quantiles: Quantiles,
+}
+
+impl Violin {
+    /// Return None if there is no valid value
+    pub fn from_vec(input: Vec<f64>, kernel: Kernel, bandwidth: Bandwidth) -> Option<Self> {
+        if input.iter().all(|x| x.is_nan()) {
+            return None;
+        }
+        Some(Self {
+            quantiles: Quantiles::from_slice(&input),
+            density: Density::from_vec(input, kernel, bandwidth),
+        })
+    }
+
+    /// Range outside of which the violin is flat
+    pub fn range(&self) -> (f64, f64) {
+        self.density
+            .range()
+            .expect("violin has at least one valid value")
+    }
+
+    /// Draw a horizontal violin on the canvas from lines height to height+lines-1
+    ///
+    /// The outline is drawn with the symbole, the quartiles on the middle line.
+    /// lines is odd, a single line only showing the quartiles.
+    pub fn draw_into(
+        &self,
+        canvas: &mut Canvas,
+        height: usize,
+        lines: usize,
+        symbole: char,
+    ) -> Result<(), CanvasError> {
+        assert!(lines % 2 == 1);
+        let half = lines / 2;
+        let middle = height + half;
+        let delta = (canvas.x_range.1 - canvas.x_range.0) / canvas.width as f64;
+        let densities: Vec<f64> = (0..canvas.width)
+            .map(|c| self.density.get_value(canvas.x_range.0 + delta * c as f64))
+            .collect();
+        let d_max = densities.iter().copied().fold(0.0, f64::max);
+        if d_max > 0.0 {
+            let mut previous = 0;
+            for (c, d) in densities.into_iter().enumerate() {
+                let extent = (d / d_max * half as f64).round() as usize;
+                if extent == 0 && d / d_max > 0.05 {
+                    canvas.set_cell(middle, c, symbole)?;
+                }
+                // link with the previous column to get a continuous outline
+                for e in extent.min(previous + 1).max(1)..=extent {
+                    canvas.set_cell(middle - e, c, symbole)?;
+                    canvas.set_cell(middle + e, c, symbole)?;
+                }
+                for e in (extent + 1)..previous {
+                    canvas.set_cell(middle - e, c, symbole)?;
+                    canvas.set_cell(middle + e, c, symbole)?;
+                }
+                previous = extent;
+            }
+        }
+        self.quantiles.draw_quartiles_into(canvas, middle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn violin_draw() {
+        let values = vec![0.0, 1.0, 1.0, 2.0, 2.0, 2.0, 3.0, 3.0, 4.0];
+        let v = Violin::from_vec(values, Kernel::default(), Bandwidth::default()).unwrap();
+        let mut canvas = Canvas::from_size(5, 40);
+        let (x_min, x_max) = v.range();
+        canvas.set_x_range(x_min, x_max);
+        v.draw_into(&mut canvas, 0, 5, 'v').unwrap();
+        let lines: Vec<String> = canvas.to_string().lines().map(String::from).collect();
+        // mirrored outline
+        assert_eq!(lines[0], lines[4]);
+        assert_eq!(lines[1], lines[3]);
+        assert!(lines[0].contains('v'));
+        assert_eq!(lines[2].matches('|').count(), 3);
+    }
+
+    #[test]
+    fn violin_constant() {
+        let v = Violin::from_vec(vec![2.0; 5], Kernel::default(), Bandwidth::default()).unwrap();
+        let mut canvas = Canvas::from_size(5, 40);
+        let (x_min, x_max) = v.range();
+        assert!(x_min < 2.0 && 2.0 < x_max);
+        canvas.set_x_range(x_min, x_max);
+        v.draw_into(&mut canvas, 0, 5, 'v').unwrap();
+        let lines: Vec<String> = canvas.to_string().lines().map(String::from).collect();
+        assert_eq!(lines[0], lines[4]);
+        // quartiles and whiskers at the same cell
+        assert_eq!(lines[2].matches('|').count(), 1);
+    }
+
+    #[test]
+    fn violin_empty() {
+        assert!(
+            Violin::from_vec(vec![f64::NAN], Kernel::default(), Bandwidth::default()).is_none()
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Detect modifications of a file by polling its metadata
+///
+/// A file is considered modified when its modification time or its size
+/// changes, so that rewrites within the resolution of the file system
+/// timestamps are still noticed when the size differs.
+///
+/// On Linux, inotify wakes the watcher up as soon as the directory of the
+/// file changes, polling being the fallback when it is not available.
+#[derive(Debug)]
+pub struct FileWatcher {
+    path: PathBuf,
+    /// (modification time, size) when last checked
+    last: Option<(SystemTime, u64)>,
+    #[cfg(target_os = "linux")]
+    inotify: Option<inotify::Inotify>,
+}
+
+impl FileWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            #[cfg(target_os = "linux")]
+            inotify: inotify::Inotify::new(&path),
+            path,
+            last: None,
+        }
+    }
+
+    /// Wait until the file may have been modified, at most timeout
+    ///
+    /// Without inotify, the whole timeout is waited.
+    pub fn wait(&self, timeout: Duration) {
+        #[cfg(target_os = "linux")]
+        if let Some(inotify) = &self.inotify {
+            inotify.wait(timeout);
+            return;
+        }
+        thread::sleep(timeout);
+    }
+
+    /// Whether the file has been modified since the last call
+    ///
+    /// The first call always returns true. A missing file (e.g. being
+    /// replaced) is not considered modified.
+    pub fn changed(&mut self) -> io::Result<bool> {
+        let metadata = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        let current = Some((metadata.modified()?, metadata.len()));
+        let changed = current != self.last;
+        self.last = current;
+        Ok(changed)
+    }
+
+    /// Consider the file as modified at the next check
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Minimal bindings to the inotify API of the C library
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::ffi::{CString, c_char, c_int, c_short, c_ulong, c_void};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::time::Duration;
+
+    const IN_NONBLOCK: c_int = 0o4000;
+    const IN_CLOEXEC: c_int = 0o2000000;
+    const IN_MODIFY: u32 = 0x2;
+    const IN_ATTRIB: u32 = 0x4;
+    const IN_CLOSE_WRITE: u32 = 0x8;
+    const IN_MOVED_TO: u32 = 0x80;
+    const IN_CREATE: u32 = 0x100;
+    const IN_DELETE: u32 = 0x200;
+    const POLLIN: c_short = 0x1;
+
+    #[repr(C)]
+    struct PollFd {
+        fd: c_int,
+        events: c_short,
+        revents: c_short,
+    }
+
+    unsafe extern "C" {
+        fn inotify_init1(flags: c_int) -> c_int;
+        fn inotify_add_watch(fd: c_int, path: *const c_char, mask: u32) -> c_int;
+        fn poll(fds: *mut PollFd, nfds: c_ulong, timeout: c_int) -> c_int;
+        fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+    }
+
+    /// Events of the directory of a file, so that the file being replaced
+    /// (removed then created, or renamed over) is noticed
+    #[derive(Debug)]
+    pub struct Inotify {
+        fd: OwnedFd,
+    }
+
+    impl Inotify {
+        /// None if inotify is not available, e.g. with too many watches
+        pub fn new(path: &Path) -> Option<Self> {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            let dir = CString::new(dir.as_os_str().as_bytes()).ok()?;
+            // SAFETY: the descriptor is owned as soon as it is valid
+            let fd = unsafe { inotify_init1(IN_NONBLOCK | IN_CLOEXEC) };
+            if fd < 0 {
+                return None;
+            }
+            let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+            let mask = IN_MODIFY | IN_ATTRIB | IN_CLOSE_WRITE | IN_MOVED_TO | IN_CREATE | IN_DELETE;
+            // SAFETY: dir is a nul terminated string outliving the call
+            if unsafe { inotify_add_watch(fd.as_raw_fd(), dir.as_ptr(), mask) } < 0 {
+                return None;
+            }
+            Some(Self { fd })
+        }
+
+        /// Wait for events at most timeout, then discard them
+        pub fn wait(&self, timeout: Duration) {
+            let mut fds = PollFd {
+                fd: self.fd.as_raw_fd(),
+                events: POLLIN,
+                revents: 0,
+            };
+            let timeout = timeout.as_millis().min(c_int::MAX as u128) as c_int;
+            let mut buffer = [0u8; 4096];
+            // SAFETY: fds and buffer outlive the calls, reads being non blocking
+            unsafe {
+                poll(&mut fds, 1, timeout);
+                while read(fds.fd, buffer.as_mut_ptr().cast(), buffer.len()) > 0 {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watch_file() {
+        let path = std::env::temp_dir().join(format!("qdplot-watch-{}.csv", std::process::id()));
+        fs::write(&path, "x,a\n0,1\n").unwrap();
+        let mut watcher = FileWatcher::new(&path);
+        assert!(watcher.changed().unwrap());
+        assert!(!watcher.changed().unwrap());
+        fs::write(&path, "x,a\n0,1\n1,2\n").unwrap();
+        assert!(watcher.changed().unwrap());
+        watcher.reset();
+        assert!(watcher.changed().unwrap());
+        fs::remove_file(&path).unwrap();
+        assert!(!watcher.changed().unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn watch_notified() {
+        let path = std::env::temp_dir().join(format!("qdplot-notify-{}.csv", std::process::id()));
+        fs::write(&path, "x,a\n0,1\n").unwrap();
+        let watcher = FileWatcher::new(&path);
+        let writer = {
+            let path = path.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                fs::write(&path, "x,a\n0,1\n1,2\n").unwrap();
+            })
+        };
+        let start = std::time::Instant::now();
+        watcher.wait(Duration::from_secs(30));
+        assert!(start.elapsed() < Duration::from_secs(10));
+        writer.join().unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+}